@@ -0,0 +1,52 @@
+//! Benchmarks for drawing frames of the spinning cube.
+//!
+//! Each benchmark draws frames with Scene::render_frame, which projects, culls and rasterizes the cube
+//! without printing anything or sleeping, at a few different screen sizes.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use simple_rust_cube::{shapes, Camera, Framebuffer, Matrix, Object, Options, Scene};
+
+///SIZES holds the width and height of the frames that are drawn.
+const SIZES : [(usize, usize); 3] = [(80, 40), (200, 100), (400, 200)];
+
+///This function returns a scene with the cube in it, with the faces filled so that every part of the drawing is measured.
+fn cube_scene() -> Scene {
+    let (vertices, faces) = shapes::cube();
+    let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+    let options = Options { fill: true, ..Options::default() };
+    Scene { options, camera: Camera::default(), objects: vec![Object::new(vertices, faces, Vec::new(), Matrix::identity())] }
+}
+
+fn render_frame(c: &mut Criterion) {
+    let scene = cube_scene();
+    for (width, height) in SIZES {
+        let mut frame_number = 0;
+        c.bench_function(&format!("render_frame {}x{}", width, height), |b| {
+            b.iter(|| {
+                // Different frames turn the cube to different angles, so the frames aren't all the same.
+                frame_number += 1;
+                scene.render_frame(black_box(frame_number), width, height)
+            })
+        });
+    }
+}
+
+///This benchmark draws every frame into the same framebuffer, like the render loop does, so nothing is allocated for the frame.
+fn render_frame_into(c: &mut Criterion) {
+    let scene = cube_scene();
+    for (width, height) in SIZES {
+        let mut frame = Framebuffer::new(width, height);
+        let mut samples = Framebuffer::new(0, 0);
+        let mut frame_number = 0;
+        c.bench_function(&format!("render_frame_into {}x{}", width, height), |b| {
+            b.iter(|| {
+                frame_number += 1;
+                scene.render_frame_into(black_box(frame_number), &mut frame, &mut samples);
+            })
+        });
+    }
+}
+
+criterion_group!(benches, render_frame, render_frame_into);
+criterion_main!(benches);
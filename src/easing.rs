@@ -0,0 +1,79 @@
+//! Easing functions, which change how motion speeds up and slows down between two keyframes (--easing).
+//!
+//! Each one takes how far through the motion it is, t, from 0.0 at the start to 1.0 at the end,
+//! and returns how far along the way the motion should be, which is also 0.0 at the start and 1.0 at the end.
+
+///NAMES holds the names that can be passed to --easing.
+pub const NAMES : [&str; 3] = ["linear", "smoothstep", "cubic"];
+
+///The Easing enum is one of the easing functions in this module.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    Smoothstep,
+    EaseInOutCubic,
+}
+
+impl Easing {
+    ///This function returns the easing with the given name from NAMES, or None if there is no easing with that name.
+    pub fn by_name(name: &str) -> Option<Easing> {
+        match name {
+            "linear" => Some(Easing::Linear),
+            "smoothstep" => Some(Easing::Smoothstep),
+            "cubic" => Some(Easing::EaseInOutCubic),
+            _ => None,
+        }
+    }
+
+    ///This function applies the easing to t.
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => linear(t),
+            Easing::Smoothstep => smoothstep(t),
+            Easing::EaseInOutCubic => ease_in_out_cubic(t),
+        }
+    }
+}
+
+///This function returns t as it is, clamped between 0.0 and 1.0, so the motion goes at the same speed all the way.
+pub fn linear(t: f32) -> f32 {
+    t.clamp(0.0, 1.0)
+}
+
+///This function starts and ends the motion gently, with the curve 3t^2 - 2t^3, which is flat at both ends.
+pub fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+///This function speeds up along 4t^3 for the first half of the motion and slows down the same way for the second half.
+///It starts and stops more sharply than smoothstep, and moves faster in the middle.
+pub fn ease_in_out_cubic(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        let u = 2.0 - 2.0 * t;
+        1.0 - u * u * u * 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for name in NAMES {
+            let easing = Easing::by_name(name).unwrap();
+            assert_eq!((easing.apply(0.0), easing.apply(1.0)), (0.0, 1.0), "{}", name);
+            assert_eq!((easing.apply(-0.5), easing.apply(1.5)), (0.0, 1.0), "{}", name);
+        }
+    }
+
+    #[test]
+    fn smoothstep_never_goes_backwards() {
+        let values: Vec<f32> = (0..=100).map(|i| smoothstep(i as f32 / 100.0)).collect();
+        assert!(values.windows(2).all(|pair| pair[1] >= pair[0]));
+    }
+}
@@ -0,0 +1,202 @@
+//! Saving rendered frames as images, for debugging and for making videos.
+//!
+//! Each cell of the framebuffer becomes one pixel. Empty cells, which hold the character the frame was cleared to,
+//! are black and anything drawn is white.
+//! Frames can be saved one at a time as PPM images, or all together as an animated GIF that loops forever.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::Framebuffer;
+
+///BLACK and WHITE are the gray levels used for empty and drawn cells.
+const BLACK : u8 = 0;
+const WHITE : u8 = 255;
+
+///This function returns the gray level of every cell of the frame, one byte per cell, row by row from the top.
+pub fn gray_pixels(frame: &Framebuffer) -> Vec<u8> {
+    frame.cells.iter().map(|&c| if c == frame.fill { BLACK } else { WHITE }).collect()
+}
+
+///This function writes the frame to path as a binary (P6) PPM image, with one pixel for each cell.
+///The header gives the width, the height and the largest value of a color, and is followed by a red, green and blue byte
+///for each pixel. They are all the same, which makes the image gray.
+pub fn write_ppm(frame: &Framebuffer, path: &str) -> std::io::Result<()> {
+    let mut data = format!("P6\n{} {}\n255\n", frame.width, frame.height).into_bytes();
+    for gray in gray_pixels(frame) {
+        data.extend_from_slice(&[gray, gray, gray]);
+    }
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&data)
+}
+
+///This function returns the path of the image for a frame inside dir. Frames are numbered from 1, like frame_0001.ppm.
+pub fn frame_path(dir: &str, frame_number: u64) -> String {
+    std::path::Path::new(dir).join(format!("frame_{:04}.ppm", frame_number)).to_string_lossy().into_owned()
+}
+
+///GIF_PALETTE is the color table of the GIF, as red, green and blue bytes. Index 0 is black and index 1 is white,
+///and palette_index picks one of them for each cell.
+const GIF_PALETTE : [u8; 6] = [BLACK, BLACK, BLACK, WHITE, WHITE, WHITE];
+
+///MIN_CODE_SIZE is the number of bits needed for a palette index. GIF doesn't allow less than 2, even for two colors.
+const MIN_CODE_SIZE : u8 = 2;
+
+///MAX_CODES is the most codes an LZW table can hold in a GIF, which is as many as fit in 12 bits.
+const MAX_CODES : u16 = 4096;
+
+///This function returns the index in GIF_PALETTE for a cell holding c, in a frame whose empty cells are fill.
+pub fn palette_index(c: char, fill: char) -> u8 {
+    if c == fill { 0 } else { 1 }
+}
+
+///This function writes the frames to path as an animated GIF that loops forever, showing each frame for delay.
+pub fn write_gif(frames: &[Framebuffer], delay: std::time::Duration, path: &str) -> std::io::Result<()> {
+    let data = encode_gif(frames, delay)?;
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&data)
+}
+
+///This function returns the bytes of an animated GIF of the frames, which all have to be the same size.
+///The size of the image is taken from the first frame, and it fails if that doesn't fit in the 16 bits GIF allows.
+///GIF measures time in hundredths of a second, so delay is rounded down to that.
+pub fn encode_gif(frames: &[Framebuffer], delay: std::time::Duration) -> std::io::Result<Vec<u8>> {
+    let (width, height) = frames.first().map_or((0, 0), |frame| (frame.width, frame.height));
+    let too_big = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "frame is too big for a GIF");
+    let width = u16::try_from(width).map_err(|_| too_big())?;
+    let height = u16::try_from(height).map_err(|_| too_big())?;
+    let delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+
+    let mut data = b"GIF89a".to_vec();
+    // The logical screen descriptor: the size, then a flag saying a global color table of 2 colors follows.
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&[0x80, 0, 0]);
+    data.extend_from_slice(&GIF_PALETTE);
+    // The NETSCAPE2.0 extension makes the animation loop. A loop count of 0 means forever.
+    data.extend_from_slice(&[0x21, 0xff, 0x0b]);
+    data.extend_from_slice(b"NETSCAPE2.0");
+    data.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for frame in frames {
+        // The graphic control extension holds how long the frame is shown.
+        data.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        data.extend_from_slice(&delay.to_le_bytes());
+        data.extend_from_slice(&[0x00, 0x00]);
+        // The image descriptor places the image at the top left corner and covers the whole screen.
+        data.extend_from_slice(&[0x2c, 0, 0, 0, 0]);
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.push(0x00);
+        data.push(MIN_CODE_SIZE);
+        let indices: Vec<u8> = frame.cells.iter().map(|&c| palette_index(c, frame.fill)).collect();
+        // The compressed data is split into blocks of up to 255 bytes, each starting with its length.
+        for block in lzw_encode(&indices, MIN_CODE_SIZE).chunks(255) {
+            data.push(block.len() as u8);
+            data.extend_from_slice(block);
+        }
+        data.push(0x00);
+    }
+    data.push(0x3b);
+    Ok(data)
+}
+
+///This function compresses palette indices with the LZW variant that GIF uses.
+///Codes below 1 << min_code_size stand for single indices, the next two are the clear and end codes,
+///and every code after that stands for a run of indices seen before. Codes start one bit wider than min_code_size
+///and grow a bit at a time as the table fills, up to 12 bits. When the table is full a clear code is written
+///and it starts again. The codes are packed into bytes starting from the lowest bit.
+pub fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+
+    let mut bytes = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    let mut write_code = |code: u16, code_size: u8| {
+        bits |= (code as u32) << bit_count;
+        bit_count += code_size;
+        while bit_count >= 8 {
+            bytes.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    };
+
+    write_code(clear, code_size);
+    let mut indices = indices.iter();
+    if let Some(&first) = indices.next() {
+        let mut prefix = first as u16;
+        for &index in indices {
+            if let Some(&code) = table.get(&(prefix, index)) {
+                prefix = code;
+                continue;
+            }
+            write_code(prefix, code_size);
+            if next < MAX_CODES {
+                table.insert((prefix, index), next);
+                next += 1;
+                if next > 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            } else {
+                write_code(clear, code_size);
+                table.clear();
+                next = end + 1;
+                code_size = min_code_size + 1;
+            }
+            prefix = index as u16;
+        }
+        write_code(prefix, code_size);
+    }
+    write_code(end, code_size);
+    if bit_count > 0 {
+        bytes.push(bits as u8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_cells_are_black_whatever_the_background_is() {
+        let mut frame = Framebuffer::new(3, 1);
+        frame.clear('.');
+        frame.set(1, 0, '#');
+        assert_eq!(gray_pixels(&frame), vec![BLACK, WHITE, BLACK]);
+        assert_eq!(palette_index('.', frame.fill), 0);
+        assert_eq!(palette_index(' ', frame.fill), 1);
+    }
+
+    #[test]
+    fn ppm_has_a_header_and_three_bytes_for_each_cell() {
+        let mut frame = Framebuffer::new(4, 3);
+        frame.set(2, 1, '#');
+        let path = std::env::temp_dir().join(format!("simple-rust-cube-test-{}.ppm", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        write_ppm(&frame, &path).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let header = b"P6\n4 3\n255\n";
+        assert!(data.starts_with(header));
+        let pixels = &data[header.len()..];
+        assert_eq!(pixels.len(), 4 * 3 * 3);
+        assert_eq!(pixels[(4 + 2) * 3..(4 + 3) * 3], [WHITE; 3]);
+        assert_eq!(pixels.iter().filter(|&&value| value == WHITE).count(), 3);
+    }
+
+    #[test]
+    fn gif_starts_with_the_magic_and_the_screen_size() {
+        let frames = vec![Framebuffer::new(300, 7); 2];
+        let data = encode_gif(&frames, std::time::Duration::from_millis(30)).unwrap();
+        assert_eq!(&data[..6], b"GIF89a");
+        // The width and height are little-endian.
+        assert_eq!(data[6..10], [44, 1, 7, 0]);
+        assert_eq!(data.last(), Some(&0x3b));
+    }
+}
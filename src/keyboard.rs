@@ -0,0 +1,165 @@
+//! Keyboard controls for the interactive mode (--interactive).
+//!
+//!   up / down     tilt the cube around the x axis
+//!   left / right  turn the cube around the y axis
+//!   space         pause or restart the automatic spin
+//!   q             quit
+//!
+//! The terminal is put into raw mode so that keys arrive as soon as they are pressed,
+//! and reads never wait, so the cube keeps spinning on its own when no key is pressed.
+
+///STEP is how far one key press turns the cube, in radians.
+const STEP : f32 = 0.1;
+
+///These are the keys that do something in interactive mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Quit,
+}
+
+///The Controls struct holds the extra rotation that has been added with the keyboard,
+///on top of the automatic spin, and whether the spin is paused.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Controls {
+    pub x_angle: f32,
+    pub y_angle: f32,
+    pub paused: bool,
+    pub quit: bool,
+}
+
+impl Controls {
+    ///This function updates the controls for one key press.
+    pub fn apply(&mut self, key: Key) {
+        match key {
+            Key::Up => self.x_angle -= STEP,
+            Key::Down => self.x_angle += STEP,
+            Key::Left => self.y_angle -= STEP,
+            Key::Right => self.y_angle += STEP,
+            Key::Space => self.paused = !self.paused,
+            Key::Quit => self.quit = true,
+        }
+    }
+}
+
+///This function turns the bytes read from the terminal into keys.
+///Arrow keys arrive as the escape sequences ESC [ A to ESC [ D, and any other bytes are ignored.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i..] {
+            [0x1b, b'[', arrow, ..] => {
+                match arrow {
+                    b'A' => keys.push(Key::Up),
+                    b'B' => keys.push(Key::Down),
+                    b'C' => keys.push(Key::Right),
+                    b'D' => keys.push(Key::Left),
+                    _ => {}
+                }
+                i += 3;
+                continue;
+            }
+            [b' ', ..] => keys.push(Key::Space),
+            [b'q', ..] | [b'Q', ..] => keys.push(Key::Quit),
+            _ => {}
+        }
+        i += 1;
+    }
+    keys
+}
+
+///The RawMode struct switches the terminal on standard input into raw mode while it exists.
+///Line buffering and echo are turned off and reads return straight away, even when nothing has been typed.
+///The original settings are put back when it is dropped.
+///Raw mode is only available on Unix, and on other systems enable always fails, so the cube just spins on its own.
+#[cfg(unix)]
+pub struct RawMode {
+    original: libc::termios,
+}
+
+#[cfg(not(unix))]
+pub struct RawMode;
+
+#[cfg(unix)]
+impl RawMode {
+    ///This function turns on raw mode. It fails if standard input is not a terminal.
+    pub fn enable() -> std::io::Result<RawMode> {
+        // SAFETY: termios is a plain C struct that tcgetattr fills in completely.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(RawMode { original })
+    }
+
+    ///This function returns the keys that have been pressed since it was last called, without waiting.
+    pub fn read_keys(&self) -> Vec<Key> {
+        let mut bytes = Vec::new();
+        let mut buffer = [0u8; 64];
+        loop {
+            // SAFETY: the buffer is valid for writes of its whole length.
+            let count = unsafe { libc::read(libc::STDIN_FILENO, buffer.as_mut_ptr().cast(), buffer.len()) };
+            if count <= 0 {
+                break;
+            }
+            bytes.extend_from_slice(&buffer[..count as usize]);
+        }
+        parse_keys(&bytes)
+    }
+}
+
+#[cfg(not(unix))]
+impl RawMode {
+    ///This function always fails, because raw mode needs a Unix terminal.
+    pub fn enable() -> std::io::Result<RawMode> {
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "raw mode is only available on Unix"))
+    }
+
+    ///This function never returns any keys, because there is no way to read them without waiting.
+    pub fn read_keys(&self) -> Vec<Key> {
+        Vec::new()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keys_reads_arrows_space_and_quit() {
+        let keys = parse_keys(b"\x1b[A\x1b[Dx q\x1b[Z");
+        assert_eq!(keys, vec![Key::Up, Key::Left, Key::Space, Key::Quit]);
+    }
+
+    #[test]
+    fn space_pauses_and_restarts_the_spin() {
+        let mut controls = Controls::default();
+        controls.apply(Key::Space);
+        assert!(controls.paused);
+        controls.apply(Key::Right);
+        controls.apply(Key::Space);
+        assert!(!controls.paused);
+        assert_eq!(controls.y_angle, STEP);
+    }
+}
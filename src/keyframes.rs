@@ -0,0 +1,117 @@
+//! Keyframes that turn the cube to given orientations at given times, instead of spinning it (--keyframes).
+//!
+//! A keyframes file has one keyframe on each line: the time in seconds, and the angles in radians around the x, y and z axes.
+//! Blank lines and lines starting with # are skipped, and the times have to go up from one line to the next:
+//!
+//!   # time  x    y     z
+//!   0.0     0.0  0.0   0.0
+//!   2.0     0.0  1.57  0.0
+//!   4.0     0.8  3.14  0.0
+//!
+//! Between two keyframes the angles are interpolated with one of the easing functions, linearly unless --easing picks another,
+//! and before the first one or after the last one
+//! the cube stays where that keyframe puts it.
+
+use crate::easing::Easing;
+use crate::{rotate_x, rotate_y, rotate_z, Matrix};
+
+///The Keyframe struct is one orientation of the cube, given as angles around the x, y and z axes, and the time in seconds it is reached at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub angles: [f32; 3],
+}
+
+///The KeyframeError enum describes why a keyframes file could not be loaded, in the same way as ObjError.
+///Io is for when the file can't be read, Parse is for a line that doesn't make sense, with its line number (starting at 1),
+///and Empty is for a file with no keyframes in it.
+#[derive(Debug)]
+pub enum KeyframeError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    Empty,
+}
+
+impl std::fmt::Display for KeyframeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KeyframeError::Io(error) => write!(f, "could not read keyframes file: {}", error),
+            KeyframeError::Parse { line, message } => write!(f, "keyframes line {}: {}", line, message),
+            KeyframeError::Empty => write!(f, "keyframes file has no keyframes"),
+        }
+    }
+}
+
+load_error!(KeyframeError);
+
+///This function loads the keyframes file at path. See parse_keyframes for the details.
+pub fn load_keyframes(path: &str) -> Result<Vec<Keyframe>, KeyframeError> {
+    let text = std::fs::read_to_string(path)?;
+    parse_keyframes(&text)
+}
+
+///This function reads the keyframes out of the text of a keyframes file, in the form described at the top of this module.
+pub fn parse_keyframes(text: &str) -> Result<Vec<Keyframe>, KeyframeError> {
+    let mut keyframes: Vec<Keyframe> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: String| KeyframeError::Parse { line: number + 1, message };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values: Vec<f32> = line.split_whitespace()
+            .map(|word| word.parse().ok().filter(|value: &f32| value.is_finite()).ok_or_else(|| error(format!("invalid number '{}'", word))))
+            .collect::<Result<_, _>>()?;
+        let [time, x, y, z] = values[..] else {
+            return Err(error("keyframe needs a time and x, y and z angles".to_string()));
+        };
+        if keyframes.last().is_some_and(|last| time <= last.time) {
+            return Err(error(format!("time {} is not after the keyframe before it", time)));
+        }
+        keyframes.push(Keyframe { time, angles: [x, y, z] });
+    }
+    if keyframes.is_empty() {
+        return Err(KeyframeError::Empty);
+    }
+    Ok(keyframes)
+}
+
+///This function returns the angles around the x, y and z axes at time seconds, interpolated between the keyframes either side of it
+///with easing, which is applied to how far the time is between them.
+///keyframes has to be in order of time, as parse_keyframes returns them, and must not be empty.
+pub fn angles_at(keyframes: &[Keyframe], time: f32, easing: Easing) -> [f32; 3] {
+    let next = keyframes.iter().position(|keyframe| keyframe.time > time);
+    let (before, after) = match next {
+        Some(0) => return keyframes[0].angles,
+        Some(i) => (keyframes[i - 1], keyframes[i]),
+        None => return keyframes[keyframes.len() - 1].angles,
+    };
+    let t = easing.apply((time - before.time) / (after.time - before.time));
+    let mut angles = [0.0; 3];
+    for (angle, (a, b)) in angles.iter_mut().zip(before.angles.iter().zip(after.angles.iter())) {
+        *angle = a + (b - a) * t;
+    }
+    angles
+}
+
+///This function returns the rotation at time seconds, which turns around the x axis, then the y axis, then the z axis
+///by the angles from angles_at, in the same order as the rotation of an object in a scene file.
+pub fn rotation_at(keyframes: &[Keyframe], time: f32, easing: Easing) -> Matrix {
+    let [x, y, z] = angles_at(keyframes, time, easing);
+    rotate_z(z) * rotate_y(y) * rotate_x(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midpoint_between_keyframes_has_the_average_angles() {
+        let keyframes = parse_keyframes("0.0 0.0 1.0 -2.0\n# halfway is at 2.0\n4.0 1.0 3.0 2.0\n").unwrap();
+        assert_eq!(angles_at(&keyframes, 2.0, Easing::Linear), [0.5, 2.0, 0.0]);
+        // Smoothstep is symmetric, so it is halfway there at the midpoint too.
+        assert_eq!(angles_at(&keyframes, 2.0, Easing::Smoothstep), [0.5, 2.0, 0.0]);
+        assert_eq!(angles_at(&keyframes, -1.0, Easing::Linear), [0.0, 1.0, -2.0]);
+        assert_eq!(angles_at(&keyframes, 9.0, Easing::Linear), [1.0, 3.0, 2.0]);
+    }
+}
@@ -0,0 +1,3310 @@
+//! A spinning text cube
+//! 
+//! 
+//! 4    +------+  6
+//!     /|     /| 
+//! 5  +------+ |  7
+//!    | |    | | 
+//! 0  | +----|-+  2
+//!    |/     |/   
+//! 1  +------+    3
+//!
+//! The math and drawing live here so other programs can use them, and main.rs only reads the
+//! options and runs the animation in the terminal.
+
+///This macro implements the traits that the error types of the file loaders all share, for an enum with an Io(std::io::Error) variant
+///and a Display that describes what went wrong. The enum is an Error, an io::Error turns into its Io variant with ?,
+///and it can be turned into an io::Error so it can be returned from main along with other errors.
+///Read errors are passed on as they are, and the others become InvalidData errors with the same message.
+macro_rules! load_error {
+    ($error:ident) => {
+        impl std::error::Error for $error {}
+
+        impl From<std::io::Error> for $error {
+            fn from(error: std::io::Error) -> $error {
+                $error::Io(error)
+            }
+        }
+
+        impl From<$error> for std::io::Error {
+            fn from(error: $error) -> std::io::Error {
+                match error {
+                    $error::Io(error) => error,
+                    _ => std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string()),
+                }
+            }
+        }
+    };
+}
+
+///This function adds the file that an error happened with to the start of its message, keeping its kind,
+///so the message that main prints says which file couldn't be read or written.
+pub fn with_context(error: std::io::Error, path: &str) -> std::io::Error {
+    std::io::Error::new(error.kind(), format!("{}: {}", path, error))
+}
+
+pub mod easing;
+pub mod export;
+pub mod keyboard;
+pub mod keyframes;
+pub mod obj;
+pub mod quaternion;
+pub mod scene;
+pub mod shapes;
+pub mod terminal;
+
+use std::ops::{Add, Mul, Sub};
+
+///This code defines a new struct called Matrix that consists of a fixed-size array of 4 arrays of 4 f32 values. 
+///The #[derive(Debug, Clone, Copy)] line is called an attribute.
+///it tells the Rust compiler to automatically implement the Debug, Clone, and Copy traits for the Matrix struct.
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix(pub [[f32; 4]; 4]);
+
+impl Matrix {
+    ///This function returns the identity matrix, which leaves any vector unchanged when they are multiplied.
+    pub fn identity() -> Matrix {
+        Matrix([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    ///This function returns the matrix with the upper-left 3x3 part made orthonormal again with the Gram-Schmidt process:
+    ///the first column is scaled to a length of 1.0, the part of the second column along the first is taken away and what is left is scaled too,
+    ///and then the same is done to the third column with the first two. The last column and the last row are left as they are.
+    ///Multiplying rotation matrices together over and over lets rounding errors pile up, until the cube starts to skew or grow.
+    ///Doing this every so often on a matrix that is built up that way turns it back into a rotation that is as close as possible to it.
+    ///main does this to the rotation it draws every frame, which is multiplied together from the spin, the keyframes and the arrow keys.
+    pub fn orthonormalize(&self) -> Matrix {
+        let column = |j: usize| Vector::direction(self.0[j][0], self.0[j][1], self.0[j][2]);
+        let x = column(0).normalize();
+        let y = (column(1) - x * dot3(&column(1), &x)).normalize();
+        let z = (column(2) - x * dot3(&column(2), &x) - y * dot3(&column(2), &y)).normalize();
+        let mut m = *self;
+        for (j, axis) in [x, y, z].iter().enumerate() {
+            m.0[j][..3].copy_from_slice(&axis.0[..3]);
+        }
+        m
+    }
+}
+
+///The default Matrix is the identity, so it leaves everything as it is.
+impl Default for Matrix {
+    fn default() -> Matrix {
+        Matrix::identity()
+    }
+}
+
+///This code defines a new struct called Vector that consists of a fixed-size array of 4 f32 values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector(pub [f32; 4]);
+
+///Vectors shorter than EPSILON are treated as having no direction at all.
+const EPSILON : f32 = 1e-6;
+
+impl Vector {
+    ///This function returns the point at x, y, z. Points have a w of 1.0, so they are moved by translations.
+    pub const fn point(x: f32, y: f32, z: f32) -> Vector {
+        Vector([x, y, z, 1.0])
+    }
+
+    ///This function returns the direction x, y, z. Directions have a w of 0.0, so translations leave them as they are
+    ///and only rotations and scales change them.
+    pub const fn direction(x: f32, y: f32, z: f32) -> Vector {
+        Vector([x, y, z, 0.0])
+    }
+
+    ///This function returns the length of the vector, using only the x, y and z components.
+    ///```
+    ///use simple_rust_cube::Vector;
+    ///assert_eq!(Vector::point(3.0, 4.0, 0.0).length(), 5.0);
+    ///```
+    pub fn length(&self) -> f32 {
+        dot3(self, self).sqrt()
+    }
+
+    ///This function returns a vector pointing the same way with a length of 1.0. The w component is kept as it is.
+    ///If the vector is too short to have a direction, a zero vector is returned instead of dividing by zero.
+    ///```
+    ///use simple_rust_cube::Vector;
+    ///assert_eq!(Vector::direction(0.0, 2.0, 0.0).normalize(), Vector::direction(0.0, 1.0, 0.0));
+    ///assert_eq!(Vector::point(0.0, 0.0, 0.0).normalize(), Vector::point(0.0, 0.0, 0.0));
+    ///```
+    pub fn normalize(&self) -> Vector {
+        let [x, y, z, w] = self.0;
+        let length = self.length();
+        if length < EPSILON {
+            return Vector([0.0, 0.0, 0.0, w]);
+        }
+        Vector([x / length, y / length, z / length, w])
+    }
+
+    ///This function returns the point that the vector stands for once x, y and z are divided by w, with a w of 1.0.
+    ///A vector with a w of about 0.0 is a direction, which has no point to divide out to, so it is returned as it is.
+    pub fn homogenize(&self) -> Vector {
+        let [x, y, z, w] = self.0;
+        if w.abs() < EPSILON {
+            return *self;
+        }
+        Vector::point(x / w, y / w, z / w)
+    }
+}
+
+///The default Vector is the point at the origin, with a w of 1.0, so it is moved by translations like any other point.
+///Use Vector::direction(0.0, 0.0, 0.0) for a zero direction instead.
+impl Default for Vector {
+    fn default() -> Vector {
+        Vector::point(0.0, 0.0, 0.0)
+    }
+}
+
+///A Vector can be made from a slice of exactly four values, x, y, z and w, like ones read from a file or a buffer.
+///Any other number of values is an error, the same one that turning the slice into a [f32; 4] gives.
+impl TryFrom<&[f32]> for Vector {
+    type Error = std::array::TryFromSliceError;
+
+    fn try_from(values: &[f32]) -> Result<Vector, Self::Error> {
+        <[f32; 4]>::try_from(values).map(Vector)
+    }
+}
+
+///These operators work componentwise on all four values, so a + b adds x to x, y to y and so on,
+///and v * 2.0 multiplies every component of v by 2.0.
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        let [ax, ay, az, aw] = self.0;
+        let [bx, by, bz, bw] = other.0;
+        Vector([ax + bx, ay + by, az + bz, aw + bw])
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        let [ax, ay, az, aw] = self.0;
+        let [bx, by, bz, bw] = other.0;
+        Vector([ax - bx, ay - by, az - bz, aw - bw])
+    }
+}
+
+impl Mul<f32> for Vector {
+    type Output = Vector;
+
+    fn mul(self, k: f32) -> Vector {
+        let [x, y, z, w] = self.0;
+        Vector([x * k, y * k, z * k, w * k])
+    }
+}
+
+///These are the vertices of a cube. Each vertex is represented as a Vector struct, 
+///which contains an array of four f32 values. The first three values represent the x, y, 
+///and z coordinates of the vertex in 3D space, and the fourth value is a homogeneous coordinate. 
+///The vertices are defined in local space, meaning that they represent the positions of the points relative to the center of the cube.
+pub const VERTICES : [Vector; 8] = [
+    Vector([-1.0, -1.0, -1.0, 1.0]),
+    Vector([-1.0, -1.0,  1.0, 1.0]),
+    Vector([ 1.0, -1.0, -1.0, 1.0]),
+    Vector([ 1.0, -1.0,  1.0, 1.0]),
+    Vector([-1.0,  1.0, -1.0, 1.0]),
+    Vector([-1.0,  1.0,  1.0, 1.0]),
+    Vector([ 1.0,  1.0, -1.0, 1.0]),
+    Vector([ 1.0,  1.0,  1.0, 1.0]),
+];
+
+//This code defines a constant array of 6 arrays of 4 u8 values called FACES. 
+///Each inner array represents the indices of the vertices of a face of a cube in 3D space. 
+///For example, the first inner array [1, 5, 7, 3] represents the vertices with indices 1, 5, 7, and 3 in a list of vertices.
+pub const FACES : [[u8; 4]; 6] = [
+    [1, 5, 7, 3],
+    [3, 7, 6, 2],
+    [0, 4, 5, 1],
+    [2, 6, 4, 0],
+    [0, 1, 3, 2],
+    [5, 4, 6, 7],
+];
+
+
+///This function, matrix_times_vector, calculates the product of a matrix and a vector.
+///The function starts by destructuring the matrix and vector into their individual components. 
+///The matrix is destructured into 4 [f32; 4] arrays, and the vector is destructured into an array of 4 f32 values.
+///The function then calculates the product of the matrix and the vector by taking the weighted sum of the columns of the matrix.
+///```
+///use simple_rust_cube::{matrix_times_vector, translation, Vector};
+///let moved = matrix_times_vector(&translation(1.0, 2.0, 3.0), &Vector::point(1.0, 1.0, 1.0));
+///assert_eq!(moved, Vector::point(2.0, 3.0, 4.0));
+///```
+pub fn matrix_times_vector(m: &Matrix, v: &Vector) -> Vector {
+    let [mx, my, mz, mw] = &m.0;
+    let [x, y, z, w] = v.0;
+    // The product is the weighted sum of the columns.
+    Vector([
+        x * mx[0] + y * my[0] + z * mz[0] + w * mw[0],
+        x * mx[1] + y * my[1] + z * mz[1] + w * mw[1],
+        x * mx[2] + y * my[2] + z * mz[2] + w * mw[2],
+        x * mx[3] + y * my[3] + z * mz[3] + w * mw[3],
+    ])
+}
+
+///This function, matrix_times_matrix, calculates the product of two matrices.
+///Each column of the result is the matrix a multiplied by the matching column of b, 
+///so the result applies b first and then a when it is used with matrix_times_vector.
+///```
+///use simple_rust_cube::{matrix_times_matrix, matrix_times_vector, scale, translation, Vector};
+///let scale_then_move = matrix_times_matrix(&translation(1.0, 0.0, 0.0), &scale(2.0, 2.0, 2.0));
+///assert_eq!(matrix_times_vector(&scale_then_move, &Vector::point(1.0, 1.0, 1.0)), Vector::point(3.0, 2.0, 2.0));
+///```
+pub fn matrix_times_matrix(a: &Matrix, b: &Matrix) -> Matrix {
+    let [bx, by, bz, bw] = b.0;
+    Matrix([
+        matrix_times_vector(a, &Vector(bx)).0,
+        matrix_times_vector(a, &Vector(by)).0,
+        matrix_times_vector(a, &Vector(bz)).0,
+        matrix_times_vector(a, &Vector(bw)).0,
+    ])
+}
+
+///a * b is the same as matrix_times_matrix(&a, &b), and m * v is the same as matrix_times_vector(&m, &v),
+///so a chain like projection * view * model * vertex reads the same way it is written in maths, applying the rightmost first.
+impl Mul for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, other: Matrix) -> Matrix {
+        matrix_times_matrix(&self, &other)
+    }
+}
+
+impl Mul<Vector> for Matrix {
+    type Output = Vector;
+
+    fn mul(self, v: Vector) -> Vector {
+        matrix_times_vector(&self, &v)
+    }
+}
+
+///This function, transpose, swaps the rows and columns of a matrix, so the value at i, j moves to j, i.
+pub fn transpose(m: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (i, column) in m.0.iter().enumerate() {
+        for (j, &value) in column.iter().enumerate() {
+            result[j][i] = value;
+        }
+    }
+    Matrix(result)
+}
+
+///This function, inverse, finds the matrix that undoes m, so that multiplying them together gives the identity matrix.
+///It uses Gauss-Jordan elimination, picking the largest value in each column as the pivot to keep the result accurate.
+///The rows of the array are treated as the rows of the matrix here. That works the same for the columns of the matrix,
+///because the inverse of the transpose is the transpose of the inverse.
+///None is returned if m is singular, which means it squashes space flat and can't be undone.
+pub fn inverse(m: &Matrix) -> Option<Matrix> {
+    let mut a = m.0;
+    let mut result = Matrix::identity().0;
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < EPSILON {
+            return None;
+        }
+        a.swap(col, pivot);
+        result.swap(col, pivot);
+        let scale = 1.0 / a[col][col];
+        for k in 0..4 {
+            a[col][k] *= scale;
+            result[col][k] *= scale;
+        }
+        for row in 0..4 {
+            if row != col {
+                let factor = a[row][col];
+                for k in 0..4 {
+                    a[row][k] -= factor * a[col][k];
+                    result[row][k] -= factor * result[col][k];
+                }
+            }
+        }
+    }
+    Some(Matrix(result))
+}
+
+///This function, dot, calculates the dot product of two vectors by summing the products of all four components.
+pub fn dot(a: &Vector, b: &Vector) -> f32 {
+    let [ax, ay, az, aw] = a.0;
+    let [bx, by, bz, bw] = b.0;
+    ax * bx + ay * by + az * bz + aw * bw
+}
+
+///This function, dot3, is the same as dot but only uses the x, y and z components,
+///so it can be used for directions in 3D space regardless of the homogeneous coordinate.
+pub fn dot3(a: &Vector, b: &Vector) -> f32 {
+    let [ax, ay, az, _] = a.0;
+    let [bx, by, bz, _] = b.0;
+    ax * bx + ay * by + az * bz
+}
+
+///This function, cross, calculates the cross product of two vectors using their x, y and z components.
+///The result is perpendicular to both a and b and follows the right-hand rule, so x cross y is z.
+///The homogeneous coordinate of the result is 0.0 because it is a direction, not a point.
+pub fn cross(a: &Vector, b: &Vector) -> Vector {
+    let [ax, ay, az, _] = a.0;
+    let [bx, by, bz, _] = b.0;
+    Vector([
+        ay * bz - az * by,
+        az * bx - ax * bz,
+        ax * by - ay * bx,
+        0.0,
+    ])
+}
+
+///X_AXIS, Y_AXIS and Z_AXIS are the directions of the x, y and z axes, for building rotations around them.
+pub const X_AXIS : Vector = Vector::direction(1.0, 0.0, 0.0);
+pub const Y_AXIS : Vector = Vector::direction(0.0, 1.0, 0.0);
+pub const Z_AXIS : Vector = Vector::direction(0.0, 0.0, 1.0);
+
+///These functions build rotation matrices that turn vectors by angle radians around the x, y or z axis.
+///A positive angle turns counterclockwise when looking down the axis towards the origin (the right-hand rule).
+///Like every Matrix in this file, each row of the array is a column of the matrix.
+pub fn rotate_x(angle: f32) -> Matrix {
+    let (c, s) = (angle.cos(), angle.sin());
+    Matrix([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0,   c,   s, 0.0],
+        [0.0,  -s,   c, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+pub fn rotate_y(angle: f32) -> Matrix {
+    let (c, s) = (angle.cos(), angle.sin());
+    Matrix([
+        [  c, 0.0,  -s, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [  s, 0.0,   c, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+pub fn rotate_z(angle: f32) -> Matrix {
+    let (c, s) = (angle.cos(), angle.sin());
+    Matrix([
+        [  c,   s, 0.0, 0.0],
+        [ -s,   c, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+///This function builds a matrix that rotates by angle radians around axis, which can point in any direction.
+///The axis is normalized first, so it can be any length, and a positive angle turns the same way as rotate_x, rotate_y and rotate_z.
+///This uses Rodrigues' rotation formula, which for a unit axis k is cos * I + sin * [k]x + (1 - cos) * k * k^T,
+///where [k]x is the matrix that takes the cross product with k. An axis with no length gives a matrix that squashes everything to the origin.
+pub fn rotate_axis(axis: Vector, angle: f32) -> Matrix {
+    let [x, y, z, _] = axis.normalize().0;
+    let (c, s) = (angle.cos(), angle.sin());
+    let t = 1.0 - c;
+    Matrix([
+        [c + x * x * t,     x * y * t + z * s, x * z * t - y * s, 0.0],
+        [x * y * t - z * s, c + y * y * t,     y * z * t + x * s, 0.0],
+        [x * z * t + y * s, y * z * t - x * s, c + z * z * t,     0.0],
+        [0.0,               0.0,               0.0,               1.0],
+    ])
+}
+
+///The SpinAxis enum is the axis that the shape spins around, which is chosen with --spin-axis.
+///Custom is any other direction, which doesn't have to have a length of 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpinAxis {
+    X,
+    Y,
+    Z,
+    Custom(Vector),
+}
+
+impl SpinAxis {
+    ///This function returns the direction of the axis.
+    pub fn vector(&self) -> Vector {
+        match self {
+            SpinAxis::X => X_AXIS,
+            SpinAxis::Y => Y_AXIS,
+            SpinAxis::Z => Z_AXIS,
+            SpinAxis::Custom(axis) => *axis,
+        }
+    }
+
+    ///This function builds the matrix that turns by angle radians around the axis, with the rotation builder for that axis.
+    fn matrix(&self, angle: f32) -> Matrix {
+        match self {
+            SpinAxis::X => rotate_x(angle),
+            SpinAxis::Y => rotate_y(angle),
+            SpinAxis::Z => rotate_z(angle),
+            SpinAxis::Custom(axis) => rotate_axis(*axis, angle),
+        }
+    }
+}
+
+///This function parses the value of --spin-axis, which is x, y or z, or the x, y and z of any other direction separated by commas, like 1,1,0.
+///A direction with no length can't be spun around, so it isn't allowed.
+fn parse_spin_axis(value: &str) -> Result<SpinAxis, String> {
+    match value {
+        "x" => return Ok(SpinAxis::X),
+        "y" => return Ok(SpinAxis::Y),
+        "z" => return Ok(SpinAxis::Z),
+        _ => {}
+    }
+    let error = || format!("the spin axis must be x, y, z or three numbers like 1,1,0, not '{}'", value);
+    let parts: Vec<f32> = value.split(',').map(|part| part.trim().parse().map_err(|_| error())).collect::<Result<_, _>>()?;
+    let [x, y, z] = parts[..] else {
+        return Err(error());
+    };
+    let axis = Vector::direction(x, y, z);
+    if !axis.length().is_finite() || axis.length() < EPSILON {
+        return Err(error());
+    }
+    Ok(SpinAxis::Custom(axis))
+}
+
+///TUMBLE_SPEEDS is the range of speeds, in radians per second, that --seed picks from.
+pub const TUMBLE_SPEEDS : std::ops::Range<f32> = 0.2..1.0;
+
+///The Rng struct is a small random number generator (SplitMix64), which is enough to pick a tumble with --seed.
+///The same seed always gives the same numbers, on every machine.
+struct Rng(u64);
+
+impl Rng {
+    ///This function returns the next 64 random bits.
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    ///This function returns a random number from 0.0 up to, but not including, 1.0.
+    fn next_f32(&mut self) -> f32 {
+        // The top 24 bits are as many as an f32 can hold exactly.
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+///This function picks the spin axis and speed for --seed from seed, so the same seed always tumbles the same way.
+///The axis is a direction with a length of 1.0 that is equally likely to point anywhere,
+///and the speed is somewhere in TUMBLE_SPEEDS.
+pub fn tumble(seed: u64) -> (SpinAxis, f32) {
+    let mut rng = Rng(seed);
+    let z = 2.0 * rng.next_f32() - 1.0;
+    let phi = std::f32::consts::TAU * rng.next_f32();
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let axis = Vector::direction(r * phi.cos(), r * phi.sin(), z);
+    let speed = TUMBLE_SPEEDS.start + (TUMBLE_SPEEDS.end - TUMBLE_SPEEDS.start) * rng.next_f32();
+    (SpinAxis::Custom(axis), speed)
+}
+
+///This function builds a translation matrix, which moves points by x, y and z.
+///The offset goes in the last column, so it is multiplied by w. Points have a w of 1.0 and are moved,
+///while directions have a w of 0.0 and are left as they are.
+pub fn translation(x: f32, y: f32, z: f32) -> Matrix {
+    let mut m = Matrix::identity();
+    m.0[3] = [x, y, z, 1.0];
+    m
+}
+
+///This function builds a scale matrix, which stretches points by sx, sy and sz along the x, y and z axes.
+pub fn scale(sx: f32, sy: f32, sz: f32) -> Matrix {
+    Matrix([
+        [ sx, 0.0, 0.0, 0.0],
+        [0.0,  sy, 0.0, 0.0],
+        [0.0, 0.0,  sz, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+///This function builds a view matrix for a camera at eye looking towards target, with up pointing up on the screen.
+///The camera's right, up and forward directions are found with cross products and scaled to a length of 1.0,
+///then they become the rows of the rotation part of the matrix, which turns world directions into camera directions.
+///The camera looks down its own negative z axis, so target ends up on that axis, directly in front of it.
+pub fn look_at(eye: Vector, target: Vector, up: Vector) -> Matrix {
+    let forward = (target - eye).normalize();
+    let right = cross(&forward, &up).normalize();
+    let up = cross(&right, &forward);
+    Matrix([
+        [right.0[0], up.0[0], -forward.0[0], 0.0],
+        [right.0[1], up.0[1], -forward.0[1], 0.0],
+        [right.0[2], up.0[2], -forward.0[2], 0.0],
+        [-dot3(&right, &eye), -dot3(&up, &eye), dot3(&forward, &eye), 1.0],
+    ])
+}
+
+///This function builds a perspective projection matrix for a camera at the origin looking down the negative z axis.
+///fov_y_radians is the vertical field of view and aspect is the width divided by the height of the view.
+///Points between the near and far planes end up with a z between -1.0 and 1.0 once they are divided by w,
+///and w itself is the distance in front of the camera, which is what makes farther things look smaller.
+pub fn perspective(fov_y_radians: f32, aspect: f32, near: f32, far: f32) -> Matrix {
+    let f = 1.0 / (fov_y_radians * 0.5).tan();
+    let depth = 1.0 / (near - far);
+    Matrix([
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, (far + near) * depth, -1.0],
+        [0.0, 0.0, 2.0 * far * near * depth, 0.0],
+    ])
+}
+
+///This function builds an orthographic projection matrix, which maps the box between left/right, bottom/top
+///and near/far onto the -1.0..1.0 range without any perspective. The w component stays 1.0,
+///so far away edges are drawn the same size as near ones and parallel edges stay parallel on screen.
+pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix {
+    let [w, h, d] = [right - left, top - bottom, far - near];
+    Matrix([
+        [2.0 / w, 0.0, 0.0, 0.0],
+        [0.0, 2.0 / h, 0.0, 0.0],
+        [0.0, 0.0, -2.0 / d, 0.0],
+        [-(right + left) / w, -(top + bottom) / h, -(far + near) / d, 1.0],
+    ])
+}
+
+///The Projection trait is a way of turning points in camera space into normalized device coordinates (NDC),
+///where everything in view is between -1.0 and 1.0. Scene::render_into picks one for the frame, and project uses it for every vertex.
+///project returns x and y, and depth returns z, which is what the z_buffer holds. Framebuffer::distance turns z back into a distance.
+///matrix is the projection matrix the projection is made from, which is also used to tell when it has changed, see Object::transform.
+pub trait Projection {
+    fn project(&self, v: &Vector) -> [f32; 2];
+    fn depth(&self, v: &Vector) -> f32;
+    fn matrix(&self) -> &Matrix;
+}
+
+///The Perspective struct is the projection made by perspective, which makes farther things smaller by dividing by w.
+#[derive(Debug, Clone, Copy)]
+pub struct Perspective {
+    matrix: Matrix,
+}
+
+impl Perspective {
+    ///This function makes a perspective projection, see perspective for what the arguments mean.
+    pub fn new(fov_y_radians: f32, aspect: f32, near: f32, far: f32) -> Perspective {
+        Perspective { matrix: perspective(fov_y_radians, aspect, near, far) }
+    }
+}
+
+impl Projection for Perspective {
+    fn project(&self, v: &Vector) -> [f32; 2] {
+        let [x, y, _] = perspective_divide(&(self.matrix * *v));
+        [x, y]
+    }
+
+    fn depth(&self, v: &Vector) -> f32 {
+        perspective_divide(&(self.matrix * *v))[2]
+    }
+
+    fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+}
+
+///The Orthographic struct is the projection made by orthographic, which draws things the same size however far away they are.
+///The w it gives is always 1.0, so there is nothing to divide by.
+#[derive(Debug, Clone, Copy)]
+pub struct Orthographic {
+    matrix: Matrix,
+}
+
+impl Orthographic {
+    ///This function makes an orthographic projection of the box between left/right, bottom/top and near/far, see orthographic.
+    pub fn new(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Orthographic {
+        Orthographic { matrix: orthographic(left, right, bottom, top, near, far) }
+    }
+}
+
+impl Projection for Orthographic {
+    fn project(&self, v: &Vector) -> [f32; 2] {
+        let [x, y, _, _] = (self.matrix * *v).0;
+        [x, y]
+    }
+
+    fn depth(&self, v: &Vector) -> f32 {
+        (self.matrix * *v).0[2]
+    }
+
+    fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+}
+
+///The SCREEN_WIDTH and SCREEN_HEIGHT constants are of type usize and represent the default width and height of the screen,
+///which are used unless --width or --height are given on the command line.
+const SCREEN_WIDTH : usize = 80;
+const SCREEN_HEIGHT : usize = 40;
+
+///FOV_Y, NEAR and FAR describe the default camera used by the perspective projection in main.
+///A 90 degree field of view fills the height of the screen with the -1.0..1.0 range in y.
+const FOV_Y : f32 = std::f32::consts::FRAC_PI_2;
+const NEAR : f32 = 0.1;
+const FAR : f32 = 100.0;
+
+///CAMERA_DISTANCE is how far the default camera sits from the center of the cube.
+const CAMERA_DISTANCE : f32 = 2.5;
+
+///MIN_CAMERA_DISTANCE is the closest that --distance can put the camera, which keeps the center of the cube beyond the near plane.
+const MIN_CAMERA_DISTANCE : f32 = 2.0 * NEAR;
+
+///The Camera struct describes where the scene is viewed from.
+///position is where the camera is, target is the point it looks at, and up is the direction that appears as up on the screen.
+///fov_y is the vertical field of view in radians, and near and far are the distances of the near and far clipping planes.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    position: Vector,
+    target: Vector,
+    up: Vector,
+    fov_y: f32,
+    near: f32,
+    far: f32,
+}
+
+impl Default for Camera {
+    ///The default camera sits CAMERA_DISTANCE along the z axis and looks back at the origin.
+    fn default() -> Camera {
+        Camera {
+            position: Vector::point(0.0, 0.0, CAMERA_DISTANCE),
+            target: Vector::point(0.0, 0.0, 0.0),
+            up: Vector::direction(0.0, 1.0, 0.0),
+            fov_y: FOV_Y,
+            near: NEAR,
+            far: FAR,
+        }
+    }
+}
+
+impl Camera {
+    ///This function returns the default camera moved to distance along the z axis, which is what --distance does.
+    ///The distance is clamped to at least MIN_CAMERA_DISTANCE.
+    pub fn at_distance(distance: f32) -> Camera {
+        let distance = distance.max(MIN_CAMERA_DISTANCE);
+        Camera { position: Vector::point(0.0, 0.0, distance), ..Camera::default() }
+    }
+
+    ///This function returns the view matrix, which moves the world so the camera is at the origin looking down the negative z axis.
+    fn view_matrix(&self) -> Matrix {
+        look_at(self.position, self.target, self.up)
+    }
+
+    ///This function returns the perspective projection for the camera, for a screen that is aspect times as wide as it is high.
+    ///The aspect is measured in the same units both ways, see screen_aspect.
+    fn projection(&self, aspect: f32) -> Perspective {
+        Perspective::new(self.fov_y, aspect, self.near, self.far)
+    }
+}
+
+///ISO_TILT and ISO_TURN are the angles that --iso tilts the world forward by and turns it around by, in radians.
+///Tilting by atan(1/sqrt(2)), about 35.26 degrees, after turning by 45 degrees lines the cube's diagonal up with the camera,
+///so its three visible faces all look the same size.
+const ISO_TILT : f32 = 0.615_479_7;
+const ISO_TURN : f32 = std::f32::consts::FRAC_PI_4;
+
+///This function returns the matrix that reflects the world in the floor, at a height of shapes::FLOOR_Y, for --mirror.
+///Heights above the floor become the same heights below it, so y is negated around the floor instead of around 0.0.
+pub fn mirror_matrix() -> Matrix {
+    translation(0.0, 2.0 * shapes::FLOOR_Y, 0.0) * scale(1.0, -1.0, 1.0)
+}
+
+///This function returns the matrix that --iso moves the world by before the camera's view matrix, see ISO_TILT.
+pub fn isometric() -> Matrix {
+    rotate_x(ISO_TILT) * rotate_y(ISO_TURN)
+}
+
+///ORTHO_SIZE is half the height of the box that the orthographic projection shows. Its width depends on the shape of the screen.
+const ORTHO_SIZE : f32 = 2.0;
+
+///CELL_ASPECT is how many times taller than it is wide a terminal cell is, unless --cell-aspect is given.
+const CELL_ASPECT : f32 = 2.0;
+
+///This function returns how many times wider than it is high a screen of width by height cells really looks.
+///Cells are taller than they are wide, so a screen with twice as many columns as rows looks square when cell_aspect is 2.0.
+///The projections use this so that the cube looks like a cube, whatever the size of the screen.
+fn screen_aspect(width: usize, height: usize, cell_aspect: f32) -> f32 {
+    width as f32 / (height as f32 * cell_aspect)
+}
+
+///DEFAULT_FPS is the number of frames drawn per second unless --fps is given, and MIN_FPS and MAX_FPS limit what --fps can ask for.
+const DEFAULT_FPS : u32 = 33;
+const MIN_FPS : u32 = 1;
+const MAX_FPS : u32 = 120;
+
+///DEFAULT_SPEED is how fast the cube spins in radians per second unless --speed is given.
+const DEFAULT_SPEED : f32 = 0.33;
+
+///MAX_SUPERSAMPLE is the biggest factor --ss can draw at. Each frame is drawn into factor * factor times as many cells,
+///so anything more would be slow without looking any smoother.
+pub const MAX_SUPERSAMPLE : usize = 4;
+
+///DEFAULT_GIF_FRAMES is how many frames go into the GIF made with --gif unless --frames is given.
+pub const DEFAULT_GIF_FRAMES : u64 = 100;
+
+///The Options struct holds the settings that can be changed from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    pub ortho: bool,
+    pub iso: bool,
+    pub fps: u32,
+    pub speed: f32,
+    pub interactive: bool,
+    pub color: bool,
+    pub fill: bool,
+    pub cull: bool,
+    pub antialias: bool,
+    pub unicode: bool,
+    pub obj: Option<String>,
+    pub stdin: bool,
+    pub shape: String,
+    pub export: Option<String>,
+    pub gif: Option<String>,
+    pub frames: Option<u64>,
+    pub count: usize,
+    pub cell_aspect: f32,
+    pub background: char,
+    pub thickness: usize,
+    pub depth_cue: bool,
+    pub stats: bool,
+    pub floor: bool,
+    pub supersample: usize,
+    pub angle: f32,
+    pub once: bool,
+    pub wrap: bool,
+    pub vertical_char: char,
+    pub horizontal_char: char,
+    pub spin_axis: SpinAxis,
+    pub labels: bool,
+    pub points: bool,
+    pub hidden_line: bool,
+    pub scene: Option<String>,
+    pub line_style: LineStyle,
+    pub gamma: f32,
+    pub min_area: f32,
+    pub distance: f32,
+    pub keyframes: Option<String>,
+    pub easing: easing::Easing,
+    pub mirror: bool,
+    pub fade: bool,
+    pub loop_period: Option<u64>,
+    pub axes: bool,
+    pub triangles: bool,
+    pub raw: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options { width: SCREEN_WIDTH, height: SCREEN_HEIGHT, ortho: false, iso: false, fps: DEFAULT_FPS, speed: DEFAULT_SPEED, interactive: false, color: false, fill: false, cull: true, antialias: false, unicode: false, obj: None, stdin: false, shape: "cube".to_string(), export: None, gif: None, frames: None, count: 1, cell_aspect: CELL_ASPECT, background: ' ', thickness: 1, depth_cue: false, stats: false, floor: false, supersample: 1, angle: 0.0, once: false, wrap: false, vertical_char: '|', horizontal_char: '-', spin_axis: SpinAxis::Y, labels: false, points: false, hidden_line: false, scene: None, line_style: LineStyle::Solid, gamma: 1.0, min_area: 0.0, distance: CAMERA_DISTANCE, keyframes: None, easing: easing::Easing::Linear, mirror: false, fade: false, loop_period: None, axes: false, triangles: false, raw: false }
+    }
+}
+
+///This function reads the command line arguments (without the program name) into an Options struct.
+///  --width N   the number of columns to draw
+///  --height N  the number of rows to draw
+///  --distance D  put the camera D away from the center of the cube instead of CAMERA_DISTANCE, see Camera::at_distance
+///  --ortho     use an orthographic projection instead of perspective
+///  --iso       look at the world from above one corner with an orthographic projection, see isometric
+///  --fps N     the number of frames to draw per second
+///  --speed R   how fast the cube spins, in radians per second
+///  --spin-axis A  spin around x, y, z, or any other direction given as x,y,z, instead of y, see parse_spin_axis
+///  --seed N    tumble around a random axis at a random speed picked from the number N, see tumble.
+///              This replaces any --spin-axis or --speed given before it
+///  --keyframes PATH  turn the shape to the orientations in a keyframes file instead of spinning it, see the keyframes module
+///  --easing E  move between keyframes with one of the easing functions in easing::NAMES instead of linearly
+///  --stats     print how long the frames take to draw and show to stderr every STATS_INTERVAL, see FrameStats
+///  --interactive  turn the cube with the arrow keys, see the keyboard module for the controls
+///  --color     draw the edges of each face in a different color
+///  --fill      fill the faces as well as drawing their edges
+///  --triangles  fill the faces by splitting them into triangles, see fill_face_triangles
+///  --gamma G   shade the filled faces with their brightness raised to the power G, see face_shade
+///  --labels    write the index of each visible face in the middle of it, see face_centroid
+///  --points    mark every vertex with POINT_CHAR
+///  --no-cull   draw the faces facing away from the camera too, so the back edges show through
+///  --min-area A  skip the faces that cover less than A cells on the screen, see face_area
+///  --hidden-line  hide the edges and lines that are behind faces, even when the faces aren't filled
+///  --fade      shade the edges so they fade from the brightness of one corner to the other, see vertex_brightness
+///  --aa        draw smoother lines with draw_line_aa
+///  --ss N      draw N times as many cells across and down and shrink them back down, for smoother edges, see Framebuffer::downsample_into.
+///              N is limited to MAX_SUPERSAMPLE
+///  --unicode   draw lines with box-drawing characters, which join up into corners where they meet
+///  --vchar C   draw steep lines with C instead of |, see parse_line_char
+///  --hchar C   draw shallow lines with C instead of -
+///  --thickness N  draw lines N cells wide, see draw_line
+///  --line-style S  draw lines solid, dashed or dotted, see LineStyle
+///  --wrap      lines that go off one side of the screen come back on the other side, see wrap_cell
+///  --depth-cue  draw everything with FILL_RAMP characters that get lighter the farther away they are, see Framebuffer::depth_cue
+///  --obj PATH  draw the mesh in an OBJ file instead of the cube
+///  --scene PATH  draw the objects and use the camera in a JSON scene file instead, see the scene module
+///  --stdin     draw the mesh in the OBJ read from standard input instead of the cube, see obj::read_obj
+///  --shape S   draw one of the built-in shapes in shapes::NAMES instead of the cube
+///  --count N   draw N copies of the shape in a grid, see grid
+///  --mirror    draw a dim reflection of the shape under it, as if the floor were a mirror, see mirror_matrix
+///  --axes      draw the x, y and z axes from the origin in red, green and blue, which turn with the shape, see shapes::axes
+///  --grid      draw a floor of lines under the shape, which stays still while the shape spins, see shapes::floor_grid
+///  --cell-aspect R  how many times taller than they are wide the terminal's cells are
+///  --background C  fill the empty cells with C instead of a space, see parse_background
+///  --export DIR  also save every frame as a PPM image in DIR, see the export module
+///  --gif PATH  save the spin as an animated GIF at PATH instead of drawing it in the terminal
+///  --frames N  stop after drawing N frames, instead of carrying on until q or Ctrl-C is pressed.
+///              This is also the number of frames put in the GIF, which is DEFAULT_GIF_FRAMES if it's not given
+///  --loop-period N  make the frames of a GIF turn the shape all the way round every N frames instead of using --speed, see loop_angle.
+///              With --frames N as well, the GIF loops smoothly
+///  --raw       write the frames to standard output as timed records that can be played back, see the terminal module
+///  --once      print a single frame as plain text and stop, see Scene::render_angle
+///  --angle R   the angle in radians that --once draws the shape turned to
+///Unknown arguments are ignored, and missing or invalid values keep the default.
+pub fn parse_args(args: &[String]) -> Options {
+    parse_args_with_defaults(args, Options::default())
+}
+
+///This function is like parse_args, but starts from defaults instead of Options::default(),
+///so main can make the screen fit the terminal unless --width or --height is given.
+pub fn parse_args_with_defaults(args: &[String], defaults: Options) -> Options {
+    let mut options = defaults;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => options.width = parse_size(args.next()).unwrap_or(options.width),
+            "--height" => options.height = parse_size(args.next()).unwrap_or(options.height),
+            "--count" => options.count = parse_size(args.next()).unwrap_or(options.count),
+            "--ss" => options.supersample = parse_size(args.next()).map_or(options.supersample, |factor| factor.min(MAX_SUPERSAMPLE)),
+            "--thickness" => options.thickness = parse_size(args.next()).unwrap_or(options.thickness),
+            "--ortho" => options.ortho = true,
+            "--iso" => {
+                options.iso = true;
+                options.ortho = true;
+            }
+            "--interactive" => options.interactive = true,
+            "--color" => options.color = true,
+            "--fill" => options.fill = true,
+            "--triangles" => options.triangles = true,
+            "--labels" => options.labels = true,
+            "--points" => options.points = true,
+            "--no-cull" => options.cull = false,
+            "--hidden-line" => options.hidden_line = true,
+            "--aa" => options.antialias = true,
+            "--fade" => options.fade = true,
+            "--unicode" => options.unicode = true,
+            "--stdin" => options.stdin = true,
+            "--depth-cue" => options.depth_cue = true,
+            "--stats" => options.stats = true,
+            "--grid" => options.floor = true,
+            "--axes" => options.axes = true,
+            "--mirror" => options.mirror = true,
+            "--wrap" => options.wrap = true,
+            "--obj" => options.obj = args.next().cloned().or(options.obj),
+            "--keyframes" => options.keyframes = args.next().cloned().or(options.keyframes),
+            "--easing" => options.easing = args.next().and_then(|name| easing::Easing::by_name(name)).unwrap_or(options.easing),
+            "--scene" => options.scene = args.next().cloned().or(options.scene),
+            "--export" => options.export = args.next().cloned().or(options.export),
+            "--gif" => options.gif = args.next().cloned().or(options.gif),
+            "--loop-period" => options.loop_period = args.next().and_then(|value| value.parse().ok()).filter(|&period| period > 0).or(options.loop_period),
+            "--frames" => options.frames = args.next().and_then(|value| value.parse().ok()).or(options.frames),
+            "--shape" => {
+                if let Some(name) = args.next().filter(|name| shapes::NAMES.contains(&name.as_str())) {
+                    options.shape = name.clone();
+                }
+            }
+            "--fps" => options.fps = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.fps),
+            "--cell-aspect" => {
+                options.cell_aspect = args.next().and_then(|value| value.parse().ok()).filter(|&aspect: &f32| aspect > 0.0).unwrap_or(options.cell_aspect)
+            }
+            "--distance" => options.distance = args.next().and_then(|value| value.parse().ok()).filter(|distance: &f32| distance.is_finite()).unwrap_or(options.distance),
+            "--min-area" => options.min_area = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.min_area),
+            "--gamma" => {
+                options.gamma = args.next().and_then(|value| value.parse().ok()).filter(|&gamma: &f32| gamma > 0.0).unwrap_or(options.gamma)
+            }
+            "--line-style" => {
+                if let Some(Ok(style)) = args.next().map(|value| parse_line_style(value)) {
+                    options.line_style = style;
+                }
+            }
+            "--vchar" => {
+                if let Some(Ok(c)) = args.next().map(|value| parse_line_char(value)) {
+                    options.vertical_char = c;
+                }
+            }
+            "--hchar" => {
+                if let Some(Ok(c)) = args.next().map(|value| parse_line_char(value)) {
+                    options.horizontal_char = c;
+                }
+            }
+            "--background" => {
+                if let Some(Ok(c)) = args.next().map(|value| parse_background(value)) {
+                    options.background = c;
+                }
+            }
+            "--speed" => options.speed = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.speed),
+            "--spin-axis" => {
+                if let Some(Ok(axis)) = args.next().map(|value| parse_spin_axis(value)) {
+                    options.spin_axis = axis;
+                }
+            }
+            "--seed" => {
+                if let Some(seed) = args.next().and_then(|value| value.parse().ok()) {
+                    (options.spin_axis, options.speed) = tumble(seed);
+                }
+            }
+            "--angle" => options.angle = args.next().and_then(|value| value.parse().ok()).unwrap_or(options.angle),
+            "--once" => options.once = true,
+            "--raw" => options.raw = true,
+            _ => {}
+        }
+    }
+    options
+}
+
+///This function returns how long each frame should be shown for to draw fps frames per second.
+///fps is clamped between MIN_FPS and MAX_FPS first.
+pub fn frame_duration(fps: u32) -> std::time::Duration {
+    let fps = fps.clamp(MIN_FPS, MAX_FPS);
+    std::time::Duration::from_millis(1000 / fps as u64)
+}
+
+///This function returns the angle the cube has turned through after spinning for elapsed time at speed radians per second.
+///Using the time instead of the frame number keeps the spin the same speed whatever the frame rate is.
+pub fn angle_at(elapsed: std::time::Duration, speed: f32) -> f32 {
+    elapsed.as_secs_f32() * speed
+}
+
+///This function returns the angle the cube has turned through at frame number frame_number with --loop-period,
+///where it makes one whole turn every period frames. Frame period is back at the same angle as frame 0, so a GIF of
+///period frames loops without a jump.
+pub fn loop_angle(frame_number: u64, period: u64) -> f32 {
+    let period = period.max(1);
+    std::f32::consts::TAU * (frame_number % period) as f32 / period as f32
+}
+
+///STATS_INTERVAL is how often the average frame times are printed with --stats.
+pub const STATS_INTERVAL : std::time::Duration = std::time::Duration::from_secs(1);
+
+///The FrameStats struct adds up how long frames took to render and to present, so their averages can be printed with --stats.
+///It is reset after each time the averages are printed, so they only cover the frames since then.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    pub frames: u32,
+    pub render: std::time::Duration,
+    pub present: std::time::Duration,
+}
+
+impl FrameStats {
+    ///This function adds the times for one frame.
+    pub fn add(&mut self, render: std::time::Duration, present: std::time::Duration) {
+        self.frames += 1;
+        self.render += render;
+        self.present += present;
+    }
+
+    ///This function returns the average time to render and to present a frame, or None if no frames have been added.
+    pub fn average(&self) -> Option<(std::time::Duration, std::time::Duration)> {
+        if self.frames == 0 {
+            return None;
+        }
+        Some((self.render / self.frames, self.present / self.frames))
+    }
+}
+
+///This function parses the value of a size argument, which has to be a whole number above zero.
+fn parse_size(value: Option<&String>) -> Option<usize> {
+    value?.parse().ok().filter(|&size| size > 0)
+}
+
+///This function parses the value of --background, which has to be a single printable ASCII character, or a space.
+///Anything else would take up more or less than one cell, and would push the rest of the row out of line.
+fn parse_background(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c == ' ' || c.is_ascii_graphic() => Ok(c),
+        _ => Err(format!("the background must be a single printable ASCII character, not '{}'", value)),
+    }
+}
+
+///This function parses the value of --line-style, which is solid, dashed or dotted.
+fn parse_line_style(value: &str) -> Result<LineStyle, String> {
+    match value {
+        "solid" => Ok(LineStyle::Solid),
+        "dashed" => Ok(LineStyle::Dashed),
+        "dotted" => Ok(LineStyle::Dotted),
+        _ => Err(format!("the line style must be solid, dashed or dotted, not '{}'", value)),
+    }
+}
+
+///This function parses the value of --vchar or --hchar, which has to be a single printable ASCII character.
+///Unlike the background, a space isn't allowed, because the lines drawn with it couldn't be seen.
+fn parse_line_char(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_graphic() => Ok(c),
+        _ => Err(format!("a line character must be a single printable ASCII character, not '{}'", value)),
+    }
+}
+
+///POINT_CHAR is the character that marks each vertex with --points.
+const POINT_CHAR : char = '+';
+
+///MIRROR_CHAR is the character that the reflection drawn by --mirror is made of, which is lighter than the lines it reflects.
+const MIRROR_CHAR : char = '.';
+
+///FACE_COLORS holds the ANSI color code used for the edges of each face in FACES when --color is given.
+///These are red, green, yellow, blue, magenta and cyan.
+const FACE_COLORS : [u8; 6] = [31, 32, 33, 34, 35, 36];
+
+///AXIS_COLORS are the ANSI color codes of the x, y and z axes drawn by --axes: red, green and blue.
+const AXIS_COLORS : [u8; 3] = [31, 32, 34];
+
+///NO_COLOR is the ANSI code that resets the color back to the terminal's default.
+const NO_COLOR : u8 = 0;
+
+///This function returns the ANSI escape sequence that switches the terminal to the given color code.
+fn ansi_color(color: u8) -> String {
+    format!("\x1b[{}m", color)
+}
+
+///DEPTH_TOLERANCE is how much farther away than the z_buffer a cell can be and still be drawn, as a fraction of how far
+///in front of the camera the z_buffer is there. Edges and fills find their depths in slightly different ways, so without it
+///faces would hide some of their own edges. The cells get bigger the farther away they are, and so do those differences,
+///which is why it is a fraction of the distance. It is compared with distances rather than the NDC depths themselves,
+///because a perspective projection squashes NDC depth more and more with distance, see Framebuffer::distance.
+const DEPTH_TOLERANCE : f32 = 0.15;
+
+///The Framebuffer struct holds the characters of one frame of the animation.
+///The cells are stored row by row in a single Vec, so cell x, y is at index y * width + x.
+///Each cell also has an ANSI color code in colors, which is taken from pen when the cell is set.
+///z_buffer holds the depth of whatever was last drawn in each cell, so that nearer things can hide farther ones.
+///coverage holds how much of each cell has been covered by anti-aliased lines, see cover.
+///The cells are chars rather than bytes so they can hold the box-drawing characters, which take more than one byte.
+///When box_drawing is set, lines are drawn with those characters instead of | - / and \, see draw_line.
+///vertical_char and horizontal_char are drawn instead of | and - by draw_line.
+///thickness is how many cells wide draw_line makes each line, and when wrap is set draw_line wraps lines around the edges, see wrap_cell.
+///When antialias is set, draw_edge draws its edges with draw_line_aa instead of draw_line.
+///fill is the character that the empty cells were last cleared to, so they can be told apart from the drawn ones, see clear.
+///projection is the projection matrix that the depths were made with, so they can be turned back into distances, see distance.
+#[derive(Debug, Clone)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+    colors: Vec<u8>,
+    z_buffer: Vec<f32>,
+    coverage: Vec<f32>,
+    pen: u8,
+    box_drawing: bool,
+    thickness: usize,
+    wrap: bool,
+    vertical_char: char,
+    horizontal_char: char,
+    antialias: bool,
+    fill: char,
+    projection: Matrix,
+}
+
+impl Framebuffer {
+    ///This function creates a framebuffer of the given size with every cell set to a space.
+    pub fn new(width: usize, height: usize) -> Framebuffer {
+        Framebuffer {
+            width,
+            height,
+            cells: vec![' '; width * height],
+            colors: vec![NO_COLOR; width * height],
+            z_buffer: vec![f32::INFINITY; width * height],
+            coverage: vec![0.0; width * height],
+            pen: NO_COLOR,
+            box_drawing: false,
+            thickness: 1,
+            wrap: false,
+            vertical_char: '|',
+            horizontal_char: '-',
+            antialias: false,
+            fill: ' ',
+            // This flips z, so the depths are the same as the distances until a projection is set.
+            projection: scale(1.0, 1.0, -1.0),
+        }
+    }
+
+    ///This function sets every cell of the framebuffer to fill, with no color, and empties the z_buffer and coverage.
+    ///```
+    ///use simple_rust_cube::Framebuffer;
+    ///let mut frame = Framebuffer::new(3, 2);
+    ///frame.set(1, 1, '#');
+    ///frame.clear('.');
+    ///assert_eq!(frame.render_to_string(), "...\n...\n");
+    ///```
+    pub fn clear(&mut self, fill: char) {
+        self.fill = fill;
+        self.cells.fill(fill);
+        self.colors.fill(NO_COLOR);
+        self.z_buffer.fill(f32::INFINITY);
+        self.coverage.fill(0.0);
+    }
+
+    ///This function sets the cell at x, y to c in the current pen color. Cells outside of the framebuffer are ignored.
+    ///```
+    ///use simple_rust_cube::Framebuffer;
+    ///let mut frame = Framebuffer::new(3, 2);
+    ///frame.set(2, 0, '#');
+    ///frame.set(3, 0, '#');
+    ///assert_eq!(frame.get(2, 0), Some('#'));
+    ///assert_eq!(frame.render_to_string(), "  #\n   \n");
+    ///```
+    pub fn set(&mut self, x: usize, y: usize, c: char) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = c;
+            self.colors[y * self.width + x] = self.pen;
+        }
+    }
+
+    ///This function is like set, but only sets the cell if depth is no farther away than what is already there.
+    ///Depths are NDC z values, so smaller is nearer. When the cell is set its depth is stored in the z_buffer.
+    ///Depths that are within DEPTH_TOLERANCE count as level, see in_front, so an edge can be drawn over the face it belongs to.
+    fn set_with_depth(&mut self, x: usize, y: usize, depth: f32, c: char) {
+        if x < self.width && y < self.height && self.in_front(y * self.width + x, depth) {
+            self.z_buffer[y * self.width + x] = depth;
+            self.set(x, y, c);
+        }
+    }
+
+    ///This function returns true if something at depth is no farther away than what is already in the z_buffer at index,
+    ///or only farther away by DEPTH_TOLERANCE of its distance from the camera.
+    fn in_front(&self, index: usize, depth: f32) -> bool {
+        let stored = self.z_buffer[index];
+        depth <= stored || self.distance(depth) <= self.distance(stored) + DEPTH_TOLERANCE * self.distance(stored).abs()
+    }
+
+    ///This function turns an NDC depth made with projection back into the distance in front of the camera,
+    ///which goes up at the same rate however far away it is, unlike the depth.
+    ///For a point at z on the axis the camera looks down, the depth is (m[2][2] * z + m[3][2]) / (m[2][3] * z + m[3][3]),
+    ///which is solved for z here, for both perspective and orthographic projections.
+    fn distance(&self, depth: f32) -> f32 {
+        let m = &self.projection.0;
+        -(m[3][2] - depth * m[3][3]) / (depth * m[2][3] - m[2][2])
+    }
+
+    ///This function adds amount to the coverage of the cell at x, y, up to a total of 1.0, and sets the cell to the FILL_RAMP
+    ///character for the new total, so a cell that a line only grazes gets a light character and one it passes through the middle
+    ///of gets a dense one. Like set_with_depth, nothing happens if something nearer than depth is already there.
+    ///Cells that would still be a space are left alone.
+    fn cover(&mut self, x: usize, y: usize, depth: f32, amount: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let total = (self.coverage[y * self.width + x] + amount).min(1.0);
+        let c = FILL_RAMP[(total * (FILL_RAMP.len() - 1) as f32).round() as usize];
+        if c != ' ' && self.in_front(y * self.width + x, depth) {
+            self.coverage[y * self.width + x] = total;
+            self.set_with_depth(x, y, depth, c);
+        }
+    }
+
+    ///This function adds the links, made from the LINK_ values, to the box-drawing character in the cell at x, y,
+    ///so that where two lines meet the cell shows a corner or a junction that joins them both.
+    ///Like set_with_depth, nothing happens if something nearer than depth is already there.
+    fn link(&mut self, x: usize, y: usize, depth: f32, links: u8) {
+        if let Some(existing) = self.get(x, y) {
+            self.set_with_depth(x, y, depth, box_char(box_links(existing) | links));
+        }
+    }
+
+    ///This function replaces every cell that has been drawn with the FILL_RAMP character for how near it is,
+    ///so the nearest cell in the frame gets the densest character and the farthest gets the lightest, see depth_brightness.
+    ///The depths are taken from the z_buffer, so this has to be done after everything has been drawn.
+    fn depth_cue(&mut self) {
+        let drawn = self.z_buffer.iter().copied().filter(|depth| depth.is_finite());
+        let (nearest, farthest) = drawn.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), depth| (min.min(depth), max.max(depth)));
+        for (cell, &depth) in self.cells.iter_mut().zip(&self.z_buffer) {
+            if depth.is_finite() {
+                *cell = FILL_RAMP[ramp_index(depth_brightness(depth, nearest, farthest))];
+            }
+        }
+    }
+
+    ///This function shrinks this framebuffer down into out, which has to be factor times smaller across and down.
+    ///Each block of factor by factor cells becomes one cell of out, with the FILL_RAMP character for how many of them have been drawn in,
+    ///so a block that is all drawn in gets the densest character and one with nothing in it is left as fill.
+    ///Any block with something in it gets at least the lightest character, so thin lines don't disappear.
+    ///The depth and the color of each cell of out are those of the nearest cell in its block.
+    fn downsample_into(&self, factor: usize, out: &mut Framebuffer, fill: char) {
+        out.clear(fill);
+        out.projection = self.projection;
+        let samples = (factor * factor) as f32;
+        for y in 0..out.height {
+            for x in 0..out.width {
+                let mut drawn = 0;
+                let mut nearest = f32::INFINITY;
+                let mut color = NO_COLOR;
+                for sy in y * factor..(y + 1) * factor {
+                    for sx in x * factor..(x + 1) * factor {
+                        let depth = self.z_buffer[sy * self.width + sx];
+                        if depth.is_finite() {
+                            drawn += 1;
+                            if depth < nearest {
+                                nearest = depth;
+                                color = self.colors[sy * self.width + sx];
+                            }
+                        }
+                    }
+                }
+                if drawn > 0 {
+                    let c = FILL_RAMP[((drawn as f32 / samples) * (FILL_RAMP.len() - 1) as f32).ceil() as usize];
+                    out.pen = color;
+                    out.set_with_depth(x, y, nearest, c);
+                }
+            }
+        }
+        out.pen = NO_COLOR;
+    }
+
+    ///This function replaces every cell that has been drawn with MIRROR_CHAR, with no color, which is how --mirror dims the reflection.
+    ///The depths are kept, so whatever is drawn afterwards still goes behind the reflection where it is farther away.
+    fn dim(&mut self) {
+        for ((cell, color), &depth) in self.cells.iter_mut().zip(self.colors.iter_mut()).zip(&self.z_buffer) {
+            if depth.is_finite() {
+                *cell = MIRROR_CHAR;
+                *color = NO_COLOR;
+            }
+        }
+    }
+
+    ///This function empties the z_buffer for every cell that is still fill, with no color, so it looks the same as after clear.
+    ///Faces that are only drawn to hide the lines behind them, with --hidden-line, leave depths in cells with nothing to see in them,
+    ///and this stops those from counting as drawn for depth_cue and downsample_into.
+    fn forget_empty_depths(&mut self, fill: char) {
+        for ((&c, &color), depth) in self.cells.iter().zip(&self.colors).zip(self.z_buffer.iter_mut()) {
+            if c == fill && color == NO_COLOR {
+                *depth = f32::INFINITY;
+            }
+        }
+    }
+
+    ///This function returns the cell at x, y, or None if it is outside of the framebuffer.
+    ///It is public so that other programs can check what was drawn where, for example the outline of the cube in a known frame.
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        if x < self.width && y < self.height {
+            Some(self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    ///This function turns the framebuffer into text, with a newline at the end of every row.
+    ///A framebuffer with no columns has no rows to write either, so it gives an empty string.
+    pub fn render_to_string(&self) -> String {
+        if self.width == 0 {
+            return String::new();
+        }
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width) {
+            text.extend(row);
+            text.push('\n');
+        }
+        text
+    }
+
+    ///This function is like render_to_string, but adds the escape sequences for the color of each cell.
+    ///A new sequence is only written when the color changes, and the color is reset at the end of every row.
+    pub fn render_to_colored_string(&self) -> String {
+        let mut text = String::new();
+        if self.width == 0 {
+            return text;
+        }
+        for (row, colors) in self.cells.chunks(self.width).zip(self.colors.chunks(self.width)) {
+            let mut current = NO_COLOR;
+            for (&c, &color) in row.iter().zip(colors) {
+                if color != current {
+                    text.push_str(&ansi_color(color));
+                    current = color;
+                }
+                text.push(c);
+            }
+            if current != NO_COLOR {
+                text.push_str(&ansi_color(NO_COLOR));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+
+///The Edge struct is an edge of a mesh, given by the vertices at its ends, and the faces it is a side of.
+///Most edges are shared by two faces, but an edge on the border of an open mesh only has one.
+#[derive(Debug, Clone, PartialEq)]
+struct Edge {
+    ends: [u32; 2],
+    faces: Vec<usize>,
+}
+
+///This function returns each edge of the faces once, however many faces share it, in the order they are first found.
+///An edge going from a to b is the same as one going from b to a, so the ends are stored with the smaller index first.
+fn unique_edges(faces: &[Vec<u32>]) -> Vec<Edge> {
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut index: std::collections::HashMap<[u32; 2], usize> = std::collections::HashMap::new();
+    for (i, face) in faces.iter().enumerate() {
+        for (start, end) in face_edges(face) {
+            let ends = [start.min(end), start.max(end)];
+            match index.get(&ends) {
+                Some(&e) => edges[e].faces.push(i),
+                None => {
+                    index.insert(ends, edges.len());
+                    edges.push(Edge { ends, faces: vec![i] });
+                }
+            }
+        }
+    }
+    edges
+}
+
+///This function returns the edges of a single face as pairs of vertices, going around the face in order,
+///starting with the one that joins the last corner back to the first.
+fn face_edges(face: &[u32]) -> impl Iterator<Item = (u32, u32)> + '_ {
+    face.iter().copied().cycle().skip(face.len().saturating_sub(1)).zip(face.iter().copied())
+}
+
+///This function returns each edge of the faces once, as the pair of vertices at its ends with the smaller index first.
+///This is all that's needed to draw any mesh as a wireframe. See unique_edges for the faces each edge belongs to as well.
+///```
+///use simple_rust_cube::edges;
+///// Two triangles that share the edge between 0 and 2.
+///let faces = vec![vec![0, 1, 2], vec![0, 2, 3]];
+///let mut found: Vec<(u32, u32)> = edges(&faces).collect();
+///found.sort();
+///assert_eq!(found, vec![(0, 1), (0, 2), (0, 3), (1, 2), (2, 3)]);
+///```
+pub fn edges(faces: &[Vec<u32>]) -> impl Iterator<Item = (u32, u32)> {
+    unique_edges(faces).into_iter().map(|edge| (edge.ends[0], edge.ends[1]))
+}
+
+///The Object struct is one thing in the scene: its geometry, and the model matrix that places it in the world.
+///Meshes have faces, which are culled and can be filled, and wireframes have lines, which are always drawn.
+///The edges of the faces are found once when the object is made, so that each one is only drawn once.
+///The positions of the vertices for the last frame are kept in cache, see Object::transform.
+#[derive(Debug, Clone)]
+pub struct Object {
+    vertices: Vec<Vector>,
+    faces: Vec<Vec<u32>>,
+    edges: Vec<Edge>,
+    lines: Vec<[u32; 2]>,
+    model: Matrix,
+    cache: std::cell::RefCell<TransformCache>,
+}
+
+impl Object {
+    ///This function makes an object from its geometry, placed by the model matrix.
+    pub fn new(vertices: Vec<Vector>, faces: Vec<Vec<u32>>, lines: Vec<[u32; 2]>, model: Matrix) -> Object {
+        let edges = unique_edges(&faces);
+        Object { vertices, faces, edges, lines, model, cache: Default::default() }
+    }
+
+    ///This function returns the positions of the vertices in camera space and on a screen of the given size,
+    ///when they are moved by cube_to_view and projected by projection.
+    ///They are only worked out again when the cache is dirty, which is when any of those have changed since the last time,
+    ///so an object that isn't moving, like when the spin is paused, isn't transformed again every frame.
+    fn transform(&self, cube_to_view: &Matrix, projection: &dyn Projection, width: usize, height: usize) -> std::cell::Ref<'_, TransformCache> {
+        let key = (cube_to_view.0, projection.matrix().0, width, height);
+        if self.cache.borrow().is_dirty(&key) {
+            let mut cache = self.cache.borrow_mut();
+            cache.view_pos = self.vertices.iter().map(|&v| *cube_to_view * v).collect();
+            cache.screen_pos = cache.view_pos.iter().map(|v| project(projection, v, width, height)).collect();
+            cache.key = Some(key);
+        }
+        self.cache.borrow()
+    }
+}
+
+///TransformKey is everything the transformed positions of an object's vertices depend on:
+///the matrix to camera space, the projection matrix and the size of the screen.
+type TransformKey = ([[f32; 4]; 4], [[f32; 4]; 4], usize, usize);
+
+///The TransformCache struct holds the positions of an object's vertices in camera space and on the screen,
+///and the key they were worked out for, which is None before the first frame.
+#[derive(Debug, Clone, Default)]
+struct TransformCache {
+    key: Option<TransformKey>,
+    view_pos: Vec<Vector>,
+    screen_pos: Vec<[f32; 3]>,
+}
+
+impl TransformCache {
+    ///This function returns true if the positions have to be worked out again for key, because they were worked out for a different one.
+    fn is_dirty(&self, key: &TransformKey) -> bool {
+        self.key.as_ref() != Some(key)
+    }
+}
+
+///GRID_SPACING is how far apart the centers of the objects made with --count are, before the grid is shrunk to fit.
+///The cube is 2.0 across, so this leaves a gap of 1.0 between neighbours.
+const GRID_SPACING : f32 = 3.0;
+
+///GRID_PHASE is how much further each object in the grid is turned than the one before, so they don't all line up.
+const GRID_PHASE : f32 = 0.5;
+
+///This function returns count copies of object laid out in a square grid, filling the rows from the top left.
+///The grid is centered on the origin and shrunk so that it is as wide as one object was, so it fits the same view.
+///Each copy is placed by its model matrix, which turns it a little, moves it to its place in the grid,
+///and then scales the whole grid down.
+pub fn grid(object: &Object, count: usize) -> Vec<Object> {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns);
+    let size = columns.max(rows) as f32;
+    let shrink = 2.0 / (GRID_SPACING * size - 1.0);
+    (0..count)
+        .map(|i| {
+            let x = ((i % columns) as f32 - (columns - 1) as f32 * 0.5) * GRID_SPACING;
+            let y = ((rows - 1) as f32 * 0.5 - (i / columns) as f32) * GRID_SPACING;
+            let place = matrix_times_matrix(&translation(x, y, 0.0), &rotate_y(i as f32 * GRID_PHASE));
+            let model = matrix_times_matrix(&scale(shrink, shrink, shrink), &place);
+            Object { model, ..object.clone() }
+        })
+        .collect()
+}
+
+///The Scene struct holds everything needed to draw a frame: the settings, the camera and the objects.
+pub struct Scene {
+    pub options: Options,
+    pub camera: Camera,
+    pub objects: Vec<Object>,
+}
+
+impl Scene {
+    ///This function returns frame number frame_number of the spin, when the frames are drawn at the --fps rate,
+    ///drawn into a new framebuffer width cells wide and height cells high.
+    ///It only depends on its arguments and doesn't print anything or wait, so the same frame always looks the same
+    ///however long it took to get to it.
+    ///```
+    ///use simple_rust_cube::{shapes, Camera, Matrix, Object, Options, Scene};
+    ///let (vertices, faces) = shapes::cube();
+    ///let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+    ///let object = Object::new(vertices, faces, Vec::new(), Matrix::identity());
+    ///let scene = Scene { options: Options::default(), camera: Camera::default(), objects: vec![object] };
+    ///let frame = scene.render_frame(5, 30, 12).render_to_string();
+    ///assert_eq!(frame.lines().count(), 12);
+    ///assert_eq!(frame, scene.render_frame(5, 30, 12).render_to_string());
+    ///```
+    pub fn render_frame(&self, frame_number: u64, width: usize, height: usize) -> Framebuffer {
+        let mut frame = Framebuffer::new(width, height);
+        self.render_frame_into(frame_number, &mut frame, &mut Framebuffer::new(0, 0));
+        frame
+    }
+
+    ///This function is like render_frame, but draws into frame, so the same framebuffer can be used for every frame.
+    ///samples is only used with --ss, see render_into.
+    ///With --loop-period the angle comes from loop_angle instead, so the spin repeats exactly.
+    pub fn render_frame_into(&self, frame_number: u64, frame: &mut Framebuffer, samples: &mut Framebuffer) {
+        let spin = match self.options.loop_period {
+            Some(period) => loop_angle(frame_number, period),
+            None => angle_at(frame_duration(self.options.fps) * frame_number as u32, self.options.speed),
+        };
+        self.render_into(&self.options.spin_axis.matrix(spin), frame, samples);
+    }
+
+    ///This function draws the objects turned angle radians around the spin axis, the same way the spin turns them,
+    ///into a new framebuffer of the given size. This is what --once draws.
+    pub fn render_angle(&self, angle: f32, width: usize, height: usize) -> Framebuffer {
+        self.render(&self.options.spin_axis.matrix(angle), width, height)
+    }
+
+    ///This function draws the objects into a new framebuffer of the given size, see render_into.
+    pub fn render(&self, rotation: &Matrix, width: usize, height: usize) -> Framebuffer {
+        let mut frame = Framebuffer::new(width, height);
+        self.render_into(rotation, &mut frame, &mut Framebuffer::new(0, 0));
+        frame
+    }
+
+    ///This function clears frame and draws the objects into it, with each one turned by rotation around its own center, see draw.
+    ///Drawing into the same framebuffer every frame saves making a new one, which matters for big screens.
+    ///With --ss the objects are drawn into samples instead, which is that many times bigger across and down, and then shrunk into frame.
+    ///samples is only made again when it isn't the right size, so passing the same one every frame, like frame, saves making a new one each time.
+    ///The factor is limited to MAX_SUPERSAMPLE.
+    pub fn render_into(&self, rotation: &Matrix, frame: &mut Framebuffer, samples: &mut Framebuffer) {
+        let options = &self.options;
+        let factor = options.supersample.min(MAX_SUPERSAMPLE);
+        if factor > 1 {
+            if (samples.width, samples.height) != (frame.width * factor, frame.height * factor) {
+                *samples = Framebuffer::new(frame.width * factor, frame.height * factor);
+            }
+            self.draw(rotation, samples);
+            samples.downsample_into(factor, frame, options.background);
+        } else {
+            self.draw(rotation, frame);
+        }
+
+        if options.depth_cue {
+            frame.depth_cue();
+        }
+    }
+
+    ///This function clears frame and draws the objects into it, with each one turned by rotation around its own center.
+    ///the cube_to_world matrix is a 4x4 matrix that to be used to rotate an object around the origin in 3D.
+    ///Each object's model matrix is applied after it, to put the object in its place.
+    ///It is combined with the camera's view matrix to give positions relative to the camera, which are then projected.
+    ///The visible faces of all the objects are sorted together, so that nearer objects are drawn over farther ones.
+    fn draw(&self, rotation: &Matrix, frame: &mut Framebuffer) {
+        let options = &self.options;
+        let camera = &self.camera;
+        let (width, height) = (frame.width, frame.height);
+        frame.clear(options.background);
+        frame.box_drawing = options.unicode;
+        frame.thickness = options.thickness;
+        frame.wrap = options.wrap;
+        frame.vertical_char = options.vertical_char;
+        frame.horizontal_char = options.horizontal_char;
+        frame.antialias = options.antialias;
+
+        let aspect = screen_aspect(width, height, options.cell_aspect);
+        let projection: Box<dyn Projection> = if options.ortho {
+            Box::new(Orthographic::new(-ORTHO_SIZE * aspect, ORTHO_SIZE * aspect, -ORTHO_SIZE, ORTHO_SIZE, camera.near, camera.far))
+        } else {
+            Box::new(camera.projection(aspect))
+        };
+        let projection = projection.as_ref();
+        frame.projection = *projection.matrix();
+        let view_matrix = if options.iso { camera.view_matrix() * isometric() } else { camera.view_matrix() };
+
+        let mut transformed = Vec::with_capacity(self.objects.len());
+        let mut center_depth: Vec<f32> = Vec::with_capacity(self.objects.len());
+        for object in &self.objects {
+            let cube_to_world = object.model * *rotation;
+            let cube_to_view = view_matrix * cube_to_world;
+            center_depth.push((cube_to_view * Vector::point(0.0, 0.0, 0.0)).0[2]);
+            transformed.push(object.transform(&cube_to_view, projection, width, height));
+        }
+        let view_pos: Vec<&[Vector]> = transformed.iter().map(|cache| &cache.view_pos[..]).collect();
+        let screen_pos: Vec<&[[f32; 3]]> = transformed.iter().map(|cache| &cache.screen_pos[..]).collect();
+
+        // Painter's algorithm: the farthest objects are drawn first, and the farthest faces of each object,
+        // so nearer faces are drawn over them. With --no-cull this includes the back faces, so the front faces are still drawn over them.
+        // Each visible face is kept as the index of its object and the index of the face in that object.
+        let mut objects: Vec<usize> = (0..self.objects.len()).collect();
+        objects.sort_by(|&a, &b| center_depth[a].total_cmp(&center_depth[b]));
+        let mut visible: Vec<(usize, usize)> = Vec::new();
+        for o in objects {
+            let (faces, screen_pos) = (&self.objects[o].faces, &screen_pos[o]);
+            visible.extend(sort_faces_by_depth(faces, view_pos[o]).into_iter()
+                .filter(|&i| {
+                    let face = &faces[i];
+                    !options.cull || !cull(screen_pos[face[0] as usize], screen_pos[face[1] as usize], screen_pos[face[2] as usize])
+                })
+                .filter(|&i| {
+                    let corners: Vec<[f32; 2]> = faces[i].iter().map(|&v| [screen_pos[v as usize][0], screen_pos[v as usize][1]]).collect();
+                    options.min_area <= 0.0 || face_area(&corners) >= options.min_area
+                })
+                .map(|i| (o, i)));
+        }
+
+        // The reflection goes in before anything else and is dimmed straight away, so nothing drawn after it is dimmed.
+        // Reflecting turns the faces inside out, so the faces that cull would hide are the ones that face the camera.
+        if options.mirror {
+            let mirror = mirror_matrix();
+            frame.pen = NO_COLOR;
+            for object in &self.objects {
+                let cube_to_view = view_matrix * mirror * object.model * *rotation;
+                let view_pos: Vec<Vector> = object.vertices.iter().map(|v| matrix_times_vector(&cube_to_view, v)).collect();
+                let screen_pos: Vec<[f32; 3]> = view_pos.iter().map(|v| project(projection, v, width, height)).collect();
+                for edge in &object.edges {
+                    let visible = edge.faces.iter().any(|&i| {
+                        let face = &object.faces[i];
+                        !options.cull || !cull(screen_pos[face[2] as usize], screen_pos[face[1] as usize], screen_pos[face[0] as usize])
+                    });
+                    if visible {
+                        let [start, end] = edge.ends;
+                        draw_edge(frame, projection, view_pos[start as usize], view_pos[end as usize], camera.near, options.line_style, None);
+                    }
+                }
+                for &[start, end] in &object.lines {
+                    draw_edge(frame, projection, view_pos[start as usize], view_pos[end as usize], camera.near, options.line_style, None);
+                }
+            }
+            frame.dim();
+        }
+
+        // The floor is drawn first, so the objects are drawn over it wherever they are in front of it.
+        // It is already in world space, and isn't turned by rotation.
+        if options.floor {
+            let (vertices, lines) = shapes::floor_grid(shapes::FLOOR_CELLS, shapes::FLOOR_SIZE);
+            let positions: Vec<Vector> = vertices.iter().map(|v| matrix_times_vector(&view_matrix, v)).collect();
+            frame.pen = NO_COLOR;
+            for [start, end] in lines {
+                draw_edge(frame, projection, positions[start as usize], positions[end as usize], camera.near, options.line_style, None);
+            }
+        }
+
+        for &(o, i) in &visible {
+            let face = &self.objects[o].faces[i];
+            let (view_pos, screen_pos) = (&view_pos[o], &screen_pos[o]);
+            frame.pen = if options.color { FACE_COLORS[i % FACE_COLORS.len()] } else { NO_COLOR };
+            // Faces that reach behind the near plane are not filled, because their corners can't be projected.
+            if (options.fill || options.hidden_line) && face.iter().all(|&v| view_pos[v as usize].0[2] < -camera.near) {
+                let corners: Vec<[f32; 3]> = face.iter().map(|&v| screen_pos[v as usize]).collect();
+                let [a, b, c] = [face[0], face[1], face[2]].map(|v| view_pos[v as usize]);
+                // For hidden lines without --fill, the face is filled with the background, so it only goes into the z_buffer.
+                let shade = if options.fill { face_shade(&face_normal(a, b, c), &LIGHT_DIR, options.gamma) } else { options.background };
+                if options.triangles {
+                    fill_face_triangles(frame, &corners, shade);
+                } else {
+                    fill_face(frame, &corners, shade);
+                }
+            }
+        }
+
+        // With --fade each vertex gets a brightness, which the edges and lines fade between. Without it fade is empty.
+        let fade: Vec<Vec<f32>> = if options.fade {
+            self.objects.iter().zip(&view_pos).map(|(object, view_pos)| {
+                let center = view_matrix * object.model * *rotation * Vector::point(0.0, 0.0, 0.0);
+                view_pos.iter().map(|v| vertex_brightness(v, &center, &LIGHT_DIR)).collect()
+            }).collect()
+        } else {
+            Vec::new()
+        };
+
+        // Each edge is drawn once, if either of its faces is visible, in the color of the nearest of them.
+        let mut order: Vec<Vec<Option<usize>>> = self.objects.iter().map(|object| vec![None; object.faces.len()]).collect();
+        for (n, &(o, i)) in visible.iter().enumerate() {
+            order[o][i] = Some(n);
+        }
+        for (o, object) in self.objects.iter().enumerate() {
+            for edge in &object.edges {
+                let Some(i) = edge.faces.iter().copied().filter(|&i| order[o][i].is_some()).max_by_key(|&i| order[o][i]) else {
+                    continue;
+                };
+                frame.pen = if options.color { FACE_COLORS[i % FACE_COLORS.len()] } else { NO_COLOR };
+                let [start, end] = edge.ends;
+                let intensity = fade.get(o).map(|brightness| [brightness[start as usize], brightness[end as usize]]);
+                draw_edge(frame, projection, view_pos[o][start as usize], view_pos[o][end as usize], camera.near, options.line_style, intensity);
+            }
+        }
+
+        frame.pen = NO_COLOR;
+        for (o, (object, view_pos)) in self.objects.iter().zip(&view_pos).enumerate() {
+            for &[start, end] in &object.lines {
+                let intensity = fade.get(o).map(|brightness| [brightness[start as usize], brightness[end as usize]]);
+                draw_edge(frame, projection, view_pos[start as usize], view_pos[end as usize], camera.near, options.line_style, intensity);
+            }
+        }
+
+        // The axes turn with rotation, but aren't moved by any object's model matrix, so they always start at the origin.
+        if options.axes {
+            let (vertices, lines) = shapes::axes(shapes::AXIS_LENGTH);
+            let axes_to_view = view_matrix * *rotation;
+            let positions: Vec<Vector> = vertices.iter().map(|v| matrix_times_vector(&axes_to_view, v)).collect();
+            for ([start, end], color) in lines.into_iter().zip(AXIS_COLORS) {
+                frame.pen = color;
+                draw_edge(frame, projection, positions[start as usize], positions[end as usize], camera.near, options.line_style, None);
+            }
+        }
+
+        // The labels go on last so the edges don't draw over them. Like the fill, faces that reach behind the near plane are skipped.
+        if options.labels {
+            for &(o, i) in &visible {
+                let face = &self.objects[o].faces[i];
+                if face.iter().all(|&v| view_pos[o][v as usize].0[2] < -camera.near) {
+                    let corners: Vec<[f32; 3]> = face.iter().map(|&v| screen_pos[o][v as usize]).collect();
+                    let [x, y, depth] = face_centroid(&corners);
+                    frame.pen = if options.color { FACE_COLORS[i % FACE_COLORS.len()] } else { NO_COLOR };
+                    plot(frame, x, y, depth, char::from_digit((i % 10) as u32, 10).unwrap_or('?'));
+                }
+            }
+        }
+
+        // Vertices are marked where they are projected, unless something nearer is in front of them or they are behind the near plane.
+        if options.points {
+            frame.pen = NO_COLOR;
+            for (view_pos, screen_pos) in view_pos.iter().zip(&screen_pos) {
+                for (v, &[x, y, depth]) in view_pos.iter().zip(screen_pos.iter()) {
+                    if v.0[2] < -camera.near {
+                        plot(frame, x, y, depth, POINT_CHAR);
+                    }
+                }
+            }
+        }
+
+        if options.hidden_line && !options.fill {
+            frame.forget_empty_depths(options.background);
+        }
+    }
+}
+
+///This function returns frame number frame_number of the default spinning cube as text, width cells wide and height cells high,
+///with every row ending in a newline. It is for drawing the cube from other programs, so nothing is printed to standard output.
+///The frame is the same one that Scene::render_frame draws with the default options.
+///```
+///let text = simple_rust_cube::render_cube_frame_string(0, 30, 12);
+///assert_eq!(text.lines().count(), 12);
+///assert!(text.lines().all(|row| row.chars().count() == 30));
+///```
+pub fn render_cube_frame_string(frame_number: u64, width: usize, height: usize) -> String {
+    let (vertices, faces) = shapes::cube();
+    let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+    let options = Options { width, height, ..Options::default() };
+    let scene = Scene { options, camera: Camera::default(), objects: vec![Object::new(vertices, faces, Vec::new(), Matrix::identity())] };
+    scene.render_frame(frame_number, width, height).render_to_string()
+}
+
+///This function, project, turns a point in camera space into a position on a screen of the given size.
+///The projection turns the point into normalized device coordinates, which leaves x and y between -1.0 and 1.0.
+///These are then turned into a screen position by ndc_to_screen.
+///The third value returned is the depth of the point from the projection.
+fn project(projection: &dyn Projection, view_pos: &Vector, width: usize, height: usize) -> [f32; 3] {
+    let [screen_x, screen_y] = ndc_to_screen(projection.project(view_pos), width, height);
+    [screen_x, screen_y, projection.depth(view_pos)]
+}
+
+///This function turns x and y in normalized device coordinates into a position on a screen of the given size.
+///They are scaled by half the width and height of the screen and offset by the same amount,
+///which stretches the -1.0..1.0 range over the whole screen with the origin in the middle.
+///So (-1.0, 1.0) is the top left corner, at (0.0, 0.0), and (1.0, -1.0) is the bottom right corner, at (width, height).
+fn ndc_to_screen([x, y]: [f32; 2], width: usize, height: usize) -> [f32; 2] {
+    let [half_width, half_height] = [width as f32 * 0.5, height as f32 * 0.5];
+    // Screen rows go down, so y is flipped to keep up pointing up.
+    [x * half_width + half_width, -y * half_height + half_height]
+}
+
+///This function turns a point in clip space, as it comes out of a projection matrix, into normalized device coordinates (NDC).
+///x, y and z are all divided by w. The perspective matrix copies the distance in front of the camera into w,
+///so this is what makes farther things smaller. The orthographic matrix leaves w as 1.0, so it changes nothing there.
+///Points inside the view end up between -1.0 and 1.0 on every axis. A point level with the camera has a w of 0.0,
+///and gives infinities or NaN, which draw_line skips. That is why this doesn't use Vector::homogenize, which leaves those points as they are.
+fn perspective_divide(clip_pos: &Vector) -> [f32; 3] {
+    let [x, y, z, w] = clip_pos.0;
+    let recip_w = 1.0 / w;
+    [x * recip_w, y * recip_w, z * recip_w]
+}
+
+///This function draws the edge between the camera space points a and b, with draw_line_aa if frame.antialias is set
+///and draw_line in style with the intensities at each end if it isn't.
+///The edge is clipped against the near plane first, so nothing behind the camera is projected.
+fn draw_edge(frame: &mut Framebuffer, projection: &dyn Projection, a: Vector, b: Vector, near: f32, style: LineStyle, intensity: Option<[f32; 2]>) {
+    if let Some((a, b)) = clip_near(a, b, near) {
+        let (width, height) = (frame.width, frame.height);
+        let (start, end) = (project(projection, &a, width, height), project(projection, &b, width, height));
+        if frame.antialias {
+            draw_line_aa(frame, start, end);
+        } else {
+            draw_line(frame, start, end, style, intensity);
+        }
+    }
+}
+
+///This function clips the edge from a to b against the near plane, which sits at z = -near in front of the camera.
+///The part of the edge that is behind the near plane is cut off, and None is returned if all of it is.
+///This is done before projection, because points at or behind the camera would divide by zero or flip over.
+fn clip_near(a: Vector, b: Vector, near: f32) -> Option<(Vector, Vector)> {
+    // These are positive in front of the near plane.
+    let da = -near - a.0[2];
+    let db = -near - b.0[2];
+    if da < 0.0 && db < 0.0 {
+        return None;
+    }
+    if da >= 0.0 && db >= 0.0 {
+        return Some((a, b));
+    }
+    let crossing = a + (b - a) * (da / (da - db));
+    if da < 0.0 {
+        Some((crossing, b))
+    } else {
+        Some((a, crossing))
+    }
+}
+
+///This function returns the centroid of the corners of a face on the screen, which is their average, with the depth averaged too.
+///The corners of a face are all in one plane, and the depth of a plane is linear across the screen, so the depth is that of the face there.
+fn face_centroid(points: &[[f32; 3]]) -> [f32; 3] {
+    let sum = points.iter().fold([0.0; 3], |[x, y, z], p| [x + p[0], y + p[1], z + p[2]]);
+    sum.map(|value| value / points.len() as f32)
+}
+
+///This function returns the average z of the corners of a face in camera space.
+///The camera looks down the negative z axis, so a lower value means the face is farther away.
+fn face_depth(face: &[u32], view_pos: &[Vector]) -> f32 {
+    face.iter().map(|&v| view_pos[v as usize].0[2]).sum::<f32>() / face.len() as f32
+}
+
+///This function returns the order to draw the faces in for the painter's algorithm, as indices into faces.
+///The faces are sorted by face_depth so the farthest one comes first and the nearest one last.
+///For a convex shape like the cube this is enough to hide the faces at the back without a z_buffer.
+fn sort_faces_by_depth(faces: &[Vec<u32>], view_pos: &[Vector]) -> Vec<usize> {
+    let depths: Vec<f32> = faces.iter().map(|face| face_depth(face, view_pos)).collect();
+    let mut order: Vec<usize> = (0..faces.len()).collect();
+    order.sort_by(|&a, &b| depths[a].total_cmp(&depths[b]));
+    order
+}
+
+///the function cull takes three parameters: p0, p1, and p2, which are screen positions as returned by project. The function returns a boolean value.
+/// indicating whether the triangle formed by the three points is clockwise or counterclockwise.
+///The sign is the z component of the cross product of the first two edges.
+///A face seen edge-on has no area on the screen, so the cross product is about zero, and it is culled too,
+///as is a face with a corner that couldn't be projected, which gives NaN. Its edges are still drawn for the faces next to it.
+///```
+///use simple_rust_cube::cull;
+///// y goes down the screen, so this goes round clockwise and faces the camera.
+///assert!(!cull([0.0, 0.0, 0.5], [4.0, 0.0, 0.5], [4.0, 4.0, 0.5]));
+///assert!(cull([0.0, 0.0, 0.5], [4.0, 4.0, 0.5], [4.0, 0.0, 0.5]));
+///assert!(cull([0.0, 0.0, 0.5], [2.0, 2.0, 0.5], [4.0, 4.0, 0.5]));
+///```
+pub fn cull(p0: [f32; 3], p1: [f32; 3], p2: [f32; 3]) -> bool {
+    let e0 = Vector::direction(p1[0] - p0[0], p1[1] - p0[1], 0.0);
+    let e1 = Vector::direction(p2[0] - p1[0], p2[1] - p1[1], 0.0);
+    let area = cross(&e0, &e1).0[2];
+    area.is_nan() || area < EPSILON
+}
+
+///This function returns the area on the screen of the polygon with corners at screen_pts, in cells, using the shoelace formula.
+///The area is the same whichever way round the corners go, and corners that all lie on one line give 0.0.
+///```
+///use simple_rust_cube::face_area;
+///assert_eq!(face_area(&[[0.0, 0.0], [3.0, 0.0], [3.0, 2.0], [0.0, 2.0]]), 6.0);
+///assert_eq!(face_area(&[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]), 0.0);
+///```
+pub fn face_area(screen_pts: &[[f32; 2]]) -> f32 {
+    let twice_area: f32 = screen_pts.iter().zip(screen_pts.iter().cycle().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum();
+    twice_area.abs() * 0.5
+}
+
+///This function is drawing a line on a 2D grid represented by the frame array.
+///The line is drawn by setting the value of certain cells in the array to the character picked by line_char for its slope.
+///Vertical and horizontal lines use frame.vertical_char and frame.horizontal_char instead of | and -, so they can be changed.
+///The line is clipped to the screen first, and any pixels that still fall outside of it are skipped.
+///start and end are screen positions with a depth, which is interpolated along the line for the z_buffer.
+///The cells are found with Bresenham's algorithm, which takes one step along the longer axis for every cell
+///and uses a whole number error term to decide when to also step along the shorter axis, so the line has no gaps.
+///When frame.thickness is more than 1, each cell is repeated that many times across the line, along the shorter axis,
+///so the line is that many cells wide. The copies are centered on the line, with the extra one below or to the right when there's an even number.
+///style picks which of the cells along the line are drawn, see LineStyle.
+///When intensity is given, it holds a brightness between 0.0 and 1.0 for start and for end. Each cell then gets the FILL_RAMP character
+///for the brightness in between them at that point on the line, instead of a line character, so the line fades from one to the other.
+///The point is found from the original start and end, so clipping the line doesn't change the brightness anywhere along it.
+///When frame.wrap is set, the cells that are off the screen are wrapped back onto it with wrap_cell, modulo its width and height,
+///so the line can go round the screen again and again. It is only cut off once it is WRAP_SCREENS screens away from where it starts.
+///```
+///use simple_rust_cube::{draw_line, Framebuffer, LineStyle};
+///let mut frame = Framebuffer::new(5, 1);
+///draw_line(&mut frame, [0.0, 0.5, 0.0], [4.0, 0.5, 0.0], LineStyle::Solid, None);
+///assert_eq!(frame.render_to_string(), "-----\n");
+///```
+pub fn draw_line(frame: &mut Framebuffer, start: [f32; 3], end: [f32; 3], style: LineStyle, intensity: Option<[f32; 2]>) {
+    if degenerate(start, end) {
+        return;
+    }
+    let (width, height) = (frame.width as f32, frame.height as f32);
+    let shift = |[x, y, z]: [f32; 3], by: [f32; 2]| [x + by[0], y + by[1], z];
+    // Wrapped cells only depend on where they are modulo the size of the screen,
+    // so the line is moved by whole screens until it starts on the screen.
+    let (start, end) = if frame.wrap {
+        let by = [-(start[0] / width.max(1.0)).floor() * width, -(start[1] / height.max(1.0)).floor() * height];
+        (shift(start, by), shift(end, by))
+    } else {
+        (start, end)
+    };
+    let (from, along) = ([start[0], start[1]], [end[0] - start[0], end[1] - start[1]]);
+    let length_squared = along[0] * along[0] + along[1] * along[1];
+    let clipped = if frame.wrap {
+        // The line is moved so the screen is in the middle of the area it's clipped to, and then moved back.
+        let (out, back) = ([width * WRAP_SCREENS, height * WRAP_SCREENS], [-width * WRAP_SCREENS, -height * WRAP_SCREENS]);
+        let size = 2.0 * WRAP_SCREENS + 1.0;
+        clip_line(shift(start, out), shift(end, out), width * size, height * size).map(|(start, end)| (shift(start, back), shift(end, back)))
+    } else {
+        clip_line(start, end, width, height)
+    };
+    let Some((start, end)) = clipped else {
+        return;
+    };
+    let (x0, y0, z0) = (start[0].floor() as i64, start[1].floor() as i64, start[2]);
+    let (x1, y1, z1) = (end[0].floor() as i64, end[1].floor() as i64, end[2]);
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let c = line_char(x1 - x0, y1 - y0);
+    // With box drawing, straight lines link each cell to the cells before and after it, see line_links.
+    // Faded lines are made of FILL_RAMP characters instead, which don't link up.
+    let links = if frame.box_drawing && intensity.is_none() { line_links(c, sx, sy) } else { None };
+    let c = match c {
+        _ if frame.box_drawing => box_diagonal(c),
+        '|' => frame.vertical_char,
+        '-' => frame.horizontal_char,
+        _ => c,
+    };
+    let steps = dx.max(-dy);
+    let thickness = frame.thickness.max(1) as i64;
+    let across = if dx >= -dy { (0, 1) } else { (1, 0) };
+    let (mut x, mut y) = (x0, y0);
+    let mut error = dx + dy;
+    for step in 0..=steps {
+        let z = if steps == 0 { z0 } else { z0 + (z1 - z0) * step as f32 / steps as f32 };
+        let c = match intensity {
+            Some([a, b]) => {
+                // How far along the line the middle of the cell is, from 0.0 at start to 1.0 at end.
+                let (cx, cy) = (x as f32 + 0.5 - from[0], y as f32 + 0.5 - from[1]);
+                let t = if length_squared > 0.0 { ((cx * along[0] + cy * along[1]) / length_squared).clamp(0.0, 1.0) } else { 0.0 };
+                FILL_RAMP[ramp_index(a + (b - a) * t)]
+            }
+            None => c,
+        };
+        // Cells that the style leaves out are skipped, but the line still steps through them.
+        if style.draws(step) {
+            for offset in (0..thickness).map(|n| n - (thickness - 1) / 2) {
+                let (x, y) = (x + offset * across.0, y + offset * across.1);
+                let (x, y) = if frame.wrap { wrap_cell(x, y, frame.width, frame.height) } else { (x, y) };
+                match links {
+                    Some((backward, forward)) if x >= 0 && y >= 0 => {
+                        // The ends only link towards the rest of the line, so that they can join up with other lines into corners.
+                        let mut cell_links = 0;
+                        if step > 0 {
+                            cell_links |= backward;
+                        }
+                        if step < steps {
+                            cell_links |= forward;
+                        }
+                        if steps == 0 {
+                            cell_links = backward | forward;
+                        }
+                        frame.link(x as usize, y as usize, z, cell_links);
+                    }
+                    Some(_) => {}
+                    None => plot(frame, x as f32, y as f32, z, c),
+                }
+            }
+        }
+        let error2 = 2 * error;
+        if error2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+///The LineStyle enum is the pattern that draw_line draws lines in. Solid lines are drawn in every cell,
+///dashed lines are drawn in three cells and then skip one, and dotted lines are drawn in every other cell.
+///Anti-aliased lines are always solid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineStyle {
+    ///This function returns true if the cell step steps along a line from its start is drawn in this style.
+    fn draws(&self, step: i64) -> bool {
+        match self {
+            LineStyle::Solid => true,
+            LineStyle::Dashed => step % 4 < 3,
+            LineStyle::Dotted => step % 2 == 0,
+        }
+    }
+}
+
+///WRAP_SCREENS is how many times a line can go round the screen in each direction with --wrap before the rest of it is cut off.
+///A line with an end that is almost infinitely far away, like a corner right next to the near plane, would otherwise take forever to draw.
+const WRAP_SCREENS : f32 = 4.0;
+
+///This function returns the cell x, y moved back onto a screen of the given size by wrapping it around the edges,
+///so a cell just off the right side is on the left side, and a cell just off the bottom is at the top.
+fn wrap_cell(x: i64, y: i64, width: usize, height: usize) -> (i64, i64) {
+    (x.rem_euclid(width.max(1) as i64), y.rem_euclid(height.max(1) as i64))
+}
+
+///This function returns true if the line from start to end can't be drawn, so draw_line and draw_line_aa skip it.
+///That is when it has no length, which happens when an edge points straight at the camera,
+///or when a point is infinite or NaN, which happens when a point couldn't be projected.
+fn degenerate(start: [f32; 3], end: [f32; 3]) -> bool {
+    if start.iter().chain(&end).any(|value| !value.is_finite()) {
+        return true;
+    }
+    (end[0] - start[0]).abs() < EPSILON && (end[1] - start[1]).abs() < EPSILON
+}
+
+///This function is an anti-aliased version of draw_line, using Xiaolin Wu's algorithm.
+///For every cell along the longer axis it works out exactly where the line crosses it, and shares the cell between
+///the two cells on either side of that point along the shorter axis. The nearer the line passes to a cell's center,
+///the more coverage it gets, and the denser the character picked for it by Framebuffer::cover.
+///This shows a shallow line as a gradient of lighter and denser characters instead of a staircase.
+///```
+///use simple_rust_cube::{draw_line_aa, Framebuffer};
+///let mut frame = Framebuffer::new(5, 3);
+///draw_line_aa(&mut frame, [0.5, 1.5, 0.5], [4.5, 1.5, 0.5]);
+///let rows: Vec<String> = frame.render_to_string().lines().map(String::from).collect();
+///assert_eq!(rows[0], "     ");
+///assert!(rows[1].chars().all(|c| c != ' '));
+///```
+pub fn draw_line_aa(frame: &mut Framebuffer, start: [f32; 3], end: [f32; 3]) {
+    if degenerate(start, end) {
+        return;
+    }
+    let Some((start, end)) = clip_line(start, end, frame.width as f32, frame.height as f32) else {
+        return;
+    };
+    // Cell centers are half a cell from their corner, so the points are moved to put the centers on whole numbers.
+    let [mut x0, mut y0, mut z0] = [start[0] - 0.5, start[1] - 0.5, start[2]];
+    let [mut x1, mut y1, mut z1] = [end[0] - 0.5, end[1] - 0.5, end[2]];
+    // A steep line is drawn with x and y swapped, so it always steps along x.
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        (x0, y0, x1, y1) = (y0, x0, y1, x1);
+    }
+    if x0 > x1 {
+        (x0, y0, z0, x1, y1, z1) = (x1, y1, z1, x0, y0, z0);
+    }
+    let gradient = if x1 - x0 < EPSILON { 0.0 } else { (y1 - y0) / (x1 - x0) };
+    let (first, last) = (x0.round() as i64, x1.round() as i64);
+    for x in first..=last {
+        let t = if last == first { 0.0 } else { (x - first) as f32 / (last - first) as f32 };
+        let z = z0 + (z1 - z0) * t;
+        let y = y0 + gradient * (x as f32 - x0);
+        let below = y.floor();
+        let fraction = y - below;
+        for (y, amount) in [(below, 1.0 - fraction), (below + 1.0, fraction)] {
+            let (px, py) = if steep { (y, x as f32) } else { (x as f32, y) };
+            if px >= 0.0 && py >= 0.0 {
+                frame.cover(px as usize, py as usize, z, amount);
+            }
+        }
+    }
+}
+
+///DIAGONAL_MIN and DIAGONAL_MAX are the range of slopes, as rows per column, that are drawn with a diagonal character.
+///They are the slopes at 22.5 and 67.5 degrees, so every line uses the character closest to its angle.
+const DIAGONAL_MIN : f32 = 0.414;
+const DIAGONAL_MAX : f32 = 2.414;
+
+///This function picks the character for a line that moves dx columns and dy rows.
+///Steep lines use a vertical bar (|), shallow lines use a horizontal dash (-), and lines near 45 degrees use a slash.
+///Rows go down the screen, so a line going up to the right uses / and a line going down to the right uses \.
+fn line_char(dx: i64, dy: i64) -> char {
+    if dx == 0 {
+        return '|';
+    }
+    let slope = dy as f32 / dx as f32;
+    if slope.abs() > DIAGONAL_MAX {
+        '|'
+    } else if slope.abs() < DIAGONAL_MIN {
+        '-'
+    } else if slope < 0.0 {
+        '/'
+    } else {
+        '\\'
+    }
+}
+
+///These are the links used for box-drawing characters. Each bit says which neighbouring cell the line in a cell reaches towards.
+const LINK_UP : u8 = 1;
+const LINK_DOWN : u8 = 2;
+const LINK_LEFT : u8 = 4;
+const LINK_RIGHT : u8 = 8;
+
+///BOX_CHARS holds the box-drawing character for every combination of links, using the links as the index.
+///A cell with no links is a space, and a cell with a single link has a line reaching from its middle to that side.
+const BOX_CHARS : [char; 16] = [
+    ' ', '╵', '╷', '│',
+    '╴', '┘', '┐', '┤',
+    '╶', '└', '┌', '├',
+    '─', '┴', '┬', '┼',
+];
+
+///This function returns the box-drawing character with the given links.
+fn box_char(links: u8) -> char {
+    BOX_CHARS[(links & 15) as usize]
+}
+
+///This function returns the links of a box-drawing character, or 0 if c isn't one of the characters in BOX_CHARS.
+fn box_links(c: char) -> u8 {
+    BOX_CHARS.iter().position(|&b| b == c && b != ' ').map_or(0, |links| links as u8)
+}
+
+///This function returns the links for a straight line drawn with c, where line_char picked c and the line steps by sx and sy.
+///The first links are towards the cell before, and the second towards the cell after. Diagonal lines have no links.
+fn line_links(c: char, sx: i64, sy: i64) -> Option<(u8, u8)> {
+    match c {
+        '-' if sx < 0 => Some((LINK_RIGHT, LINK_LEFT)),
+        '-' => Some((LINK_LEFT, LINK_RIGHT)),
+        '|' if sy < 0 => Some((LINK_DOWN, LINK_UP)),
+        '|' => Some((LINK_UP, LINK_DOWN)),
+        _ => None,
+    }
+}
+
+///This function returns the box-drawing version of a diagonal line character from line_char.
+fn box_diagonal(c: char) -> char {
+    match c {
+        '/' => '╱',
+        '\\' => '╲',
+        _ => c,
+    }
+}
+
+///These are the outcodes used by clip_line. Each bit says which side of the screen a point is on.
+const INSIDE : u8 = 0;
+const LEFT : u8 = 1;
+const RIGHT : u8 = 2;
+const ABOVE : u8 = 4;
+const BELOW : u8 = 8;
+
+///This function returns the outcode of a point, which has a bit set for each edge of the screen the point is outside of.
+fn outcode([x, y, _]: [f32; 3], width: f32, height: f32) -> u8 {
+    let mut code = INSIDE;
+    if x < 0.0 {
+        code |= LEFT;
+    } else if x > width {
+        code |= RIGHT;
+    }
+    if y < 0.0 {
+        code |= ABOVE;
+    } else if y > height {
+        code |= BELOW;
+    }
+    code
+}
+
+///This function clips the line from start to end to a screen of the given size using the Cohen-Sutherland algorithm.
+///While an endpoint is outside of the screen it is moved along the line onto the edge it is outside of.
+///The new endpoints are found in floating point, so the clipped line has the same slope as the original,
+///and their depth is interpolated the same way.
+///None is returned when the line is completely outside of the screen.
+fn clip_line(start: [f32; 3], end: [f32; 3], width: f32, height: f32) -> Option<([f32; 3], [f32; 3])> {
+    let [mut p0, mut p1] = [start, end];
+    let [mut code0, mut code1] = [outcode(p0, width, height), outcode(p1, width, height)];
+    loop {
+        if code0 | code1 == INSIDE {
+            return Some((p0, p1));
+        }
+        if code0 & code1 != INSIDE {
+            return None;
+        }
+        // Move whichever endpoint is outside onto the edge it is beyond.
+        let code = if code0 != INSIDE { code0 } else { code1 };
+        let [x0, y0, z0] = p0;
+        let [x1, y1, z1] = p1;
+        let point = if code & LEFT != 0 {
+            let t = (0.0 - x0) / (x1 - x0);
+            [0.0, y0 + (y1 - y0) * t, z0 + (z1 - z0) * t]
+        } else if code & RIGHT != 0 {
+            let t = (width - x0) / (x1 - x0);
+            [width, y0 + (y1 - y0) * t, z0 + (z1 - z0) * t]
+        } else if code & ABOVE != 0 {
+            let t = (0.0 - y0) / (y1 - y0);
+            [x0 + (x1 - x0) * t, 0.0, z0 + (z1 - z0) * t]
+        } else {
+            let t = (height - y0) / (y1 - y0);
+            [x0 + (x1 - x0) * t, height, z0 + (z1 - z0) * t]
+        };
+        if code == code0 {
+            p0 = point;
+            code0 = outcode(p0, width, height);
+        } else {
+            p1 = point;
+            code1 = outcode(p1, width, height);
+        }
+    }
+}
+
+///FILL_RAMP holds the characters used to fill faces, from lightest to densest.
+const FILL_RAMP : &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+///LIGHT_DIR is the direction from the cube towards the light, in camera space. The light is above, to the left of and behind the camera.
+const LIGHT_DIR : Vector = Vector::direction(-1.0, 1.0, 1.0);
+
+///This function returns the normal of a face from its first three corners a, b and c, which is the direction the face points in.
+///Faces list their corners clockwise as seen from outside, so the cross product of c - a and b - a points outwards.
+///```
+///use simple_rust_cube::{face_normal, Vector};
+///// Clockwise as seen from +z, so the face points towards +z.
+///let normal = face_normal(Vector::point(0.0, 0.0, 0.0), Vector::point(0.0, 1.0, 0.0), Vector::point(1.0, 0.0, 0.0));
+///assert_eq!(normal, Vector::direction(0.0, 0.0, 1.0));
+///```
+pub fn face_normal(a: Vector, b: Vector, c: Vector) -> Vector {
+    cross(&(c - a), &(b - a)).normalize()
+}
+
+///This function returns the index into FILL_RAMP for a brightness between 0.0 and 1.0.
+///Even unlit faces get at least the first character after the space, so every filled face can be seen.
+fn ramp_index(brightness: f32) -> usize {
+    let brightness = brightness.clamp(0.0, 1.0);
+    1 + (brightness * (FILL_RAMP.len() - 2) as f32).round() as usize
+}
+
+///This function returns the brightness between 0.0 and 1.0 of something at depth, for depth cueing,
+///where nearest and farthest are the depths of the nearest and farthest things in the frame.
+///The nearest thing gets 1.0 and the farthest gets 0.0, and everything in the frame gets 1.0 if it's all at the same depth.
+fn depth_brightness(depth: f32, nearest: f32, farthest: f32) -> f32 {
+    if farthest - nearest < EPSILON {
+        return 1.0;
+    }
+    1.0 - (depth - nearest) / (farthest - nearest)
+}
+
+///This function returns the character used to fill a face with the given normal, shaded by a light in the direction light_dir.
+///The brightness is the dot product of the two directions, so faces pointing straight at the light get the densest character
+///and faces at right angles to the light or facing away from it get the lightest.
+///The brightness is raised to the power of gamma before the character is picked, so a gamma above 1.0 darkens
+///the faces that are lit from the side and a gamma below 1.0 brightens them, while 1.0 leaves the brightness as it is.
+fn face_shade(normal: &Vector, light_dir: &Vector, gamma: f32) -> char {
+    let brightness = dot3(normal, &light_dir.normalize()).clamp(0.0, 1.0).powf(gamma);
+    FILL_RAMP[ramp_index(brightness)]
+}
+
+///This function returns the brightness between 0.0 and 1.0 of a vertex at position, for --fade, lit by a light in the direction light_dir.
+///The vertex is treated as facing straight out from center, the middle of its object, so the corner nearest the light is the brightest
+///and the one on the far side is the darkest.
+fn vertex_brightness(position: &Vector, center: &Vector, light_dir: &Vector) -> f32 {
+    let out = (*position - *center).normalize();
+    (dot3(&out, &light_dir.normalize()) + 1.0) * 0.5
+}
+
+///This function fills the polygon with corners at points with the character c, using a scanline fill.
+///For each row, the x positions where the edges of the polygon cross the row are found and sorted,
+///and the cells between each pair of crossings are filled. Each cell is sampled at its center, so a cell is filled when its center is inside.
+///The depth of the corners is interpolated in the same way, so each filled cell is tested against the z_buffer.
+///```
+///use simple_rust_cube::{fill_face, Framebuffer};
+///let mut frame = Framebuffer::new(4, 3);
+///fill_face(&mut frame, &[[1.0, 0.0, 0.5], [3.0, 0.0, 0.5], [3.0, 2.0, 0.5], [1.0, 2.0, 0.5]], '#');
+///assert_eq!(frame.render_to_string(), " ## \n ## \n    \n");
+///```
+pub fn fill_face(frame: &mut Framebuffer, points: &[[f32; 3]], c: char) {
+    let ymin = points.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min);
+    let ymax = points.iter().map(|p| p[1]).fold(f32::NEG_INFINITY, f32::max);
+    let iymin = clamp_to_cells((ymin - 0.5).ceil(), frame.height);
+    let iymax = clamp_to_cells((ymax - 0.5).ceil(), frame.height);
+    let mut crossings = Vec::with_capacity(points.len());
+    for iy in iymin..iymax {
+        let y = iy as f32 + 0.5;
+        crossings.clear();
+        let mut end = points[points.len() - 1];
+        for &start in points {
+            let [[x0, y0, z0], [x1, y1, z1]] = [start, end];
+            if (y0 <= y && y < y1) || (y1 <= y && y < y0) {
+                let t = (y - y0) / (y1 - y0);
+                crossings.push([x0 + (x1 - x0) * t, z0 + (z1 - z0) * t]);
+            }
+            end = start;
+        }
+        crossings.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        for pair in crossings.chunks_exact(2) {
+            let [[xa, za], [xb, zb]] = [pair[0], pair[1]];
+            let ixmin = clamp_to_cells((xa - 0.5).ceil(), frame.width);
+            let ixmax = clamp_to_cells((xb - 0.5).ceil(), frame.width);
+            for ix in ixmin..ixmax {
+                let x = ix as f32 + 0.5;
+                let z = za + (zb - za) * (x - xa) / (xb - xa);
+                plot(frame, x, y, z, c);
+            }
+        }
+    }
+}
+
+///This function fills the triangle with corners at a, b and c with the character ch, using edge functions.
+///Each corner is a screen position with a depth, like the points of fill_face. Every cell in the box around the triangle
+///is sampled at its center, and the edge functions give how far the center is inside each edge, scaled by the length of the edge.
+///The center is inside the triangle when it is inside all three, either way round, so the corners can be given in either order.
+///A center that is exactly on an edge is only filled if it is a top edge or a left edge, which means the inside of the triangle is
+///below it or to the right of it. Two triangles that share an edge then fill the cells along it once between them, not twice.
+///The three values divided by the area of the triangle are the barycentric coordinates of the center,
+///which weight the depths of the corners to give its depth for the z_buffer. A triangle with no area fills nothing.
+///```
+///use simple_rust_cube::{fill_triangle, Framebuffer};
+///let mut frame = Framebuffer::new(4, 2);
+///// The two halves of a square fill each of its cells once between them.
+///fill_triangle(&mut frame, [0.0, 0.0, 0.5], [4.0, 0.0, 0.5], [4.0, 2.0, 0.5], '#');
+///fill_triangle(&mut frame, [0.0, 0.0, 0.5], [4.0, 2.0, 0.5], [0.0, 2.0, 0.5], '+');
+///assert_eq!(frame.render_to_string(), "+###\n+++#\n");
+///```
+pub fn fill_triangle(frame: &mut Framebuffer, a: [f32; 3], b: [f32; 3], c: [f32; 3], ch: char) {
+    let edge = |from: [f32; 3], to: [f32; 3], x: f32, y: f32| (to[0] - from[0]) * (y - from[1]) - (to[1] - from[1]) * (x - from[0]);
+    let area = edge(a, b, c[0], c[1]);
+    if area.abs() < EPSILON || area.is_nan() {
+        return;
+    }
+    let ixmin = clamp_to_cells((a[0].min(b[0]).min(c[0]) - 0.5).ceil(), frame.width);
+    let ixmax = clamp_to_cells((a[0].max(b[0]).max(c[0]) - 0.5).floor() + 1.0, frame.width);
+    let iymin = clamp_to_cells((a[1].min(b[1]).min(c[1]) - 0.5).ceil(), frame.height);
+    let iymax = clamp_to_cells((a[1].max(b[1]).max(c[1]) - 0.5).floor() + 1.0, frame.height);
+    // The edge function of from and to goes up towards the inside fastest in the direction (from.y - to.y, to.x - from.x) / area.
+    // That points right for a left edge, and straight down for a top edge, as y goes down the screen.
+    let top_left = |from: [f32; 3], to: [f32; 3]| {
+        let (nx, ny) = ((from[1] - to[1]) / area, (to[0] - from[0]) / area);
+        nx > 0.0 || (nx == 0.0 && ny > 0.0)
+    };
+    let inside = |w: f32, from: [f32; 3], to: [f32; 3]| w > 0.0 || (w == 0.0 && top_left(from, to));
+    for iy in iymin..iymax {
+        let y = iy as f32 + 0.5;
+        for ix in ixmin..ixmax {
+            let x = ix as f32 + 0.5;
+            // Dividing by the area makes all three positive inside, whichever way round the corners go.
+            let [wa, wb, wc] = [edge(b, c, x, y) / area, edge(c, a, x, y) / area, edge(a, b, x, y) / area];
+            if inside(wa, b, c) && inside(wb, c, a) && inside(wc, a, b) {
+                plot(frame, x, y, wa * a[2] + wb * b[2] + wc * c[2], ch);
+            }
+        }
+    }
+}
+
+///This function fills the polygon with corners at points with the character c by splitting it into triangles that all share
+///the first corner, and filling each of them with fill_triangle. This is what --triangles fills faces with.
+///It only works for convex polygons, like all the faces of the built-in shapes.
+fn fill_face_triangles(frame: &mut Framebuffer, points: &[[f32; 3]], c: char) {
+    for pair in points[1..].windows(2) {
+        fill_triangle(frame, points[0], pair[0], pair[1], c);
+    }
+}
+
+///This function sets the cell at x, y to c, but only if the cell is on the screen and nothing nearer than depth is already there.
+///Negative, too large and NaN coordinates are ignored instead of panicking, see cell.
+fn plot(frame: &mut Framebuffer, x: f32, y: f32, depth: f32, c: char) {
+    if let Some((x, y)) = cell(x, y, frame.width, frame.height) {
+        frame.set_with_depth(x, y, depth, c);
+    }
+}
+
+///This function returns the cell that the screen position x, y is in, or None if it isn't on a screen of the given size.
+///Casting a negative float to usize would give 0, and a NaN would too, which puts points that shouldn't be drawn on the edge of the screen,
+///so those are checked for first.
+fn cell(x: f32, y: f32, width: usize, height: usize) -> Option<(usize, usize)> {
+    let inside = |value: f32, size: usize| value.is_finite() && value >= 0.0 && value < size as f32;
+    if inside(x, width) && inside(y, height) {
+        Some((x as usize, y as usize))
+    } else {
+        None
+    }
+}
+
+///This function turns value into a row or column number between 0 and size, for the ends of a range of cells.
+///Values off either side of the screen are clamped to it, and NaN gives 0, so a range with a NaN end is empty or stops at the edge.
+fn clamp_to_cells(value: f32, size: usize) -> usize {
+    if value.is_nan() {
+        return 0;
+    }
+    value.clamp(0.0, size as f32) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///EPS is how close two results have to be to count as the same in these tests.
+    pub(crate) const EPS : f32 = 1e-5;
+
+    ///This function returns true if every component of a is within eps of the same component of b, w included.
+    ///Floating point rounding means two ways of working out the same vector rarely give exactly the same values,
+    ///so this is how results are compared in these tests and the tests of the other modules.
+    pub(crate) fn approx_eq_vector(a: &Vector, b: &Vector, eps: f32) -> bool {
+        a.0.iter().zip(&b.0).all(|(x, y)| (x - y).abs() <= eps)
+    }
+
+    ///This function is like approx_eq_vector, but compares every value of two matrices.
+    pub(crate) fn approx_eq_matrix(a: &Matrix, b: &Matrix, eps: f32) -> bool {
+        a.0.iter().zip(&b.0).all(|(a, b)| approx_eq_vector(&Vector(*a), &Vector(*b), eps))
+    }
+
+    #[test]
+    fn approx_eq_allows_small_differences_only() {
+        let v = Vector::point(1.0, 2.0, 3.0);
+        assert!(approx_eq_vector(&v, &Vector::point(1.0, 2.0, 3.0 + EPS / 2.0), EPS));
+        assert!(!approx_eq_vector(&v, &Vector::point(1.0, 2.0, 3.1), EPS));
+        assert!(!approx_eq_vector(&v, &Vector::direction(1.0, 2.0, 3.0), EPS));
+        let mut m = Matrix::identity();
+        assert!(approx_eq_matrix(&m, &Matrix::identity(), EPS));
+        m.0[3][0] = 0.5;
+        assert!(!approx_eq_matrix(&m, &Matrix::identity(), EPS));
+    }
+
+    #[test]
+    fn matrix_times_identity_is_unchanged() {
+        let m = translation(1.0, 2.0, 3.0) * rotate_y(0.7) * scale(2.0, 1.0, 0.5);
+        assert!(approx_eq_matrix(&matrix_times_matrix(&m, &Matrix::identity()), &m, EPS));
+        assert!(approx_eq_matrix(&matrix_times_matrix(&Matrix::identity(), &m), &m, EPS));
+    }
+
+    #[test]
+    fn matrix_times_matrix_is_associative() {
+        let a = rotate_x(0.3);
+        let b = translation(-1.0, 0.5, 2.0);
+        let c = rotate_z(1.1) * scale(1.0, 3.0, 1.0);
+        let left = matrix_times_matrix(&matrix_times_matrix(&a, &b), &c);
+        let right = matrix_times_matrix(&a, &matrix_times_matrix(&b, &c));
+        assert!(approx_eq_matrix(&left, &right, EPS));
+    }
+
+    #[test]
+    fn orthonormalize_corrects_a_perturbed_rotation() {
+        let mut m = rotate_y(0.8) * rotate_x(0.3);
+        m.0[0][0] += 0.01;
+        m.0[1][2] -= 0.02;
+        m.0[2][1] += 0.015;
+        let fixed = m.orthonormalize();
+        let column = |j: usize| Vector::direction(fixed.0[j][0], fixed.0[j][1], fixed.0[j][2]);
+        for i in 0..3 {
+            assert!((column(i).length() - 1.0).abs() < EPS);
+            for j in i + 1..3 {
+                assert!(dot3(&column(i), &column(j)).abs() < EPS);
+            }
+        }
+        assert!(approx_eq_matrix(&fixed, &(rotate_y(0.8) * rotate_x(0.3)), 0.05));
+    }
+
+    #[test]
+    fn parse_background_takes_one_printable_character() {
+        assert_eq!(parse_background("."), Ok('.'));
+        assert_eq!(parse_background(" "), Ok(' '));
+        assert!(parse_background("").is_err());
+        assert!(parse_background("ab").is_err());
+        assert!(parse_background("\t").is_err());
+        assert!(parse_background("é").is_err());
+        let args: Vec<String> = ["--background", "xy"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(parse_args(&args).background, ' ');
+    }
+
+    #[test]
+    fn render_cube_frame_string_has_height_lines_of_width_characters() {
+        let text = render_cube_frame_string(3, 30, 12);
+        assert_eq!(text.lines().count(), 12);
+        assert!(text.lines().all(|line| line.chars().count() == 30));
+        assert!(text.chars().any(|c| c != ' ' && c != '\n'));
+    }
+
+    #[test]
+    fn render_cube_frame_string_with_no_columns_is_empty() {
+        assert_eq!(render_cube_frame_string(0, 0, 10), "");
+        assert_eq!(render_cube_frame_string(0, 10, 0), "");
+        assert_eq!(Framebuffer::new(0, 5).render_to_colored_string(), "");
+    }
+
+    #[test]
+    fn downsample_turns_full_blocks_dense_and_leaves_empty_ones() {
+        let mut big = Framebuffer::new(4, 2);
+        big.pen = 31;
+        for x in 0..2 {
+            for y in 0..2 {
+                big.set_with_depth(x, y, 0.5, '#');
+            }
+        }
+        let mut out = Framebuffer::new(2, 1);
+        big.downsample_into(2, &mut out, ' ');
+        assert_eq!(out.get(0, 0), Some(FILL_RAMP[FILL_RAMP.len() - 1]));
+        assert_eq!(out.colors[0], 31);
+        assert_eq!(out.get(1, 0), Some(' '));
+        assert_eq!(out.colors[1], NO_COLOR);
+    }
+
+    #[test]
+    fn supersample_factor_is_limited() {
+        let args: Vec<String> = ["--ss", "100"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(parse_args(&args).supersample, MAX_SUPERSAMPLE);
+    }
+
+    #[test]
+    fn wrapped_lines_come_back_on_the_other_side() {
+        assert_eq!(wrap_cell(40, 3, 40, 20), (0, 3));
+        assert_eq!(wrap_cell(-1, -1, 40, 20), (39, 19));
+        let mut frame = Framebuffer::new(10, 5);
+        frame.wrap = true;
+        draw_line(&mut frame, [8.5, 2.5, 0.0], [12.5, 2.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(frame.render_to_string().lines().nth(2), Some("---     --"));
+    }
+
+    #[test]
+    fn wrapping_is_modulo_the_screen_size() {
+        let mut near = Framebuffer::new(10, 5);
+        near.wrap = true;
+        draw_line(&mut near, [1.5, 1.5, 0.0], [4.5, 1.5, 0.0], LineStyle::Solid, None);
+        let mut far = Framebuffer::new(10, 5);
+        far.wrap = true;
+        draw_line(&mut far, [31.5, -8.5, 0.0], [34.5, -8.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(far.render_to_string(), near.render_to_string());
+    }
+
+    ///This function returns how many cells of frame aren't spaces.
+    fn drawn_cells(frame: &Framebuffer) -> usize {
+        frame.cells.iter().filter(|&&c| c != ' ').count()
+    }
+
+    #[test]
+    fn fill_triangle_fills_shared_edges_once() {
+        let (a, b, c, d) = ([0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [0.0, 10.0, 0.0], [10.0, 10.0, 0.0]);
+        let mut first = Framebuffer::new(12, 12);
+        fill_triangle(&mut first, a, b, c, '#');
+        assert_eq!(drawn_cells(&first), 45);
+        let mut second = Framebuffer::new(12, 12);
+        fill_triangle(&mut second, d, c, b, '#');
+        assert_eq!(drawn_cells(&second), 55);
+        assert!(first.cells.iter().zip(&second.cells).all(|(&x, &y)| x == ' ' || y == ' '));
+        // The same square filled from the other diagonal, with the corners the other way round, covers it the same.
+        let mut square = Framebuffer::new(12, 12);
+        fill_triangle(&mut square, b, a, d, '#');
+        fill_triangle(&mut square, c, d, a, '#');
+        assert_eq!(drawn_cells(&square), 100);
+    }
+
+    ///This function returns a scene with the given options and the cube in the middle, or count copies of it in a grid.
+    fn cube_scene(options: Options) -> Scene {
+        let (vertices, faces) = shapes::cube();
+        let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+        let objects = grid(&Object::new(vertices, faces, Vec::new(), Matrix::identity()), options.count);
+        Scene { options, camera: Camera::default(), objects }
+    }
+
+    #[test]
+    fn hidden_line_hides_the_back_edges() {
+        let rotation = rotate_x(0.4) * rotate_y(0.6);
+        for count in [1, 9] {
+            let drawn = |options: Options| drawn_cells(&cube_scene(Options { count, ..options }).render(&rotation, 80, 40));
+            let culled = drawn(Options::default());
+            let see_through = drawn(Options { cull: false, ..Options::default() });
+            let hidden = drawn(Options { cull: false, hidden_line: true, ..Options::default() });
+            // Only the silhouette can show a little more than the culled cube, where the faces at the back are almost edge on.
+            assert!(see_through > culled);
+            assert!(hidden <= culled + (see_through - culled) / 4, "{} copies: {} cells drawn, {} without the back faces", count, hidden, culled);
+        }
+    }
+
+    #[test]
+    fn nearer_quad_hides_the_overlapping_part_of_a_farther_one() {
+        let quad = |x: f32, depth: f32| [[x, 1.0, depth], [x + 6.0, 1.0, depth], [x + 6.0, 5.0, depth], [x, 5.0, depth]];
+        // The nearer one wins in the overlap whichever is drawn first.
+        for near_first in [true, false] {
+            let mut frame = Framebuffer::new(12, 6);
+            let (first, second) = if near_first { ((quad(0.0, 1.0), 'n'), (quad(4.0, 2.0), 'f')) } else { ((quad(4.0, 2.0), 'f'), (quad(0.0, 1.0), 'n')) };
+            fill_face(&mut frame, &first.0, first.1);
+            fill_face(&mut frame, &second.0, second.1);
+            assert_eq!(frame.render_to_string().lines().nth(2), Some("nnnnnnffff  "));
+        }
+        // Depths within DEPTH_TOLERANCE of each other count as level, so the last one drawn shows.
+        let mut frame = Framebuffer::new(12, 6);
+        fill_face(&mut frame, &quad(0.0, 1.0), 'n');
+        fill_face(&mut frame, &quad(4.0, 1.0 + DEPTH_TOLERANCE / 2.0), 'f');
+        assert_eq!(frame.render_to_string().lines().nth(2), Some("nnnnffffff  "));
+    }
+
+    #[test]
+    fn frame_0_has_the_outline_of_the_front_face() {
+        let frame = cube_scene(Options::default()).render_frame(0, 80, 40);
+        let drawn = |x: usize, y: usize| frame.get(x, y).is_some_and(|c| c != ' ');
+        // Frame 0 looks straight at the front face, which goes from about 13.3 to 66.7 across and 6.7 to 33.3 down.
+        // Each side only has to be somewhere within a cell of where it should be.
+        for y in 8..32 {
+            assert!((12..=14).any(|x| drawn(x, y)) && (65..=67).any(|x| drawn(x, y)), "row {}", y);
+        }
+        for x in 15..65 {
+            assert!((5..=7).any(|y| drawn(x, y)) && (32..=34).any(|y| drawn(x, y)), "column {}", x);
+        }
+        // Inside the face and well outside it nothing is drawn.
+        assert!((16..64).all(|x| (9..31).all(|y| !drawn(x, y))));
+        assert!((0..80).all(|x| (0..40).all(|y| (12..=67).contains(&x) && (5..=34).contains(&y) || !drawn(x, y))));
+    }
+
+    #[test]
+    fn frame_0_matches_the_stored_frame() {
+        // render_frame_0.txt is frame 0 as it was drawn when this test was written. If a change to the drawing is meant
+        // to change it, the file can be made again with: simple-rust-cube --once --width 80 --height 40 > testdata/render_frame_0.txt
+        let frame = cube_scene(Options::default()).render_frame(0, 80, 40);
+        assert_eq!(frame.render_to_string(), include_str!("../testdata/render_frame_0.txt"));
+    }
+
+    #[test]
+    fn identity_leaves_every_vertex_where_it_is() {
+        for v in VERTICES {
+            assert_eq!(matrix_times_vector(&Matrix::identity(), &v), v);
+        }
+    }
+
+    #[test]
+    fn full_turn_brings_the_vertices_back() {
+        let turn = 2.0 * std::f32::consts::PI;
+        for rotation in [rotate_x(turn), rotate_y(turn), rotate_z(turn)] {
+            for v in VERTICES {
+                assert!(approx_eq_vector(&matrix_times_vector(&rotation, &v), &v, EPS));
+            }
+        }
+    }
+
+    #[test]
+    fn dot_of_orthogonal_and_parallel_vectors() {
+        assert_eq!(dot3(&X_AXIS, &Y_AXIS), 0.0);
+        assert_eq!(dot3(&Vector::direction(2.0, 0.0, 0.0), &Vector::direction(3.0, 0.0, 0.0)), 6.0);
+        assert_eq!(dot3(&Vector::direction(1.0, 2.0, 3.0), &Vector::direction(-2.0, -4.0, -6.0)), -28.0);
+        // dot counts w as well, dot3 doesn't.
+        let (p, q) = (Vector::point(1.0, 0.0, 0.0), Vector::point(0.0, 1.0, 0.0));
+        assert_eq!(dot3(&p, &q), 0.0);
+        assert_eq!(dot(&p, &q), 1.0);
+    }
+
+    #[test]
+    fn cross_of_the_axes_follows_the_right_hand_rule() {
+        assert_eq!(cross(&X_AXIS, &Y_AXIS), Z_AXIS);
+        assert_eq!(cross(&Y_AXIS, &Z_AXIS), X_AXIS);
+        assert_eq!(cross(&Z_AXIS, &X_AXIS), Y_AXIS);
+        assert_eq!(cross(&Y_AXIS, &X_AXIS), Vector::direction(0.0, 0.0, -1.0));
+        assert_eq!(cross(&X_AXIS, &X_AXIS), Vector::direction(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn normalized_vectors_have_length_1() {
+        for v in [Vector::direction(3.0, 4.0, 12.0), Vector::direction(-0.001, 0.002, 0.0), Vector::point(5.0, -7.0, 1.0)] {
+            assert!((v.normalize().length() - 1.0).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn add_is_commutative_and_mul_scales_every_component() {
+        let (a, b) = (Vector::point(1.0, -2.0, 0.5), Vector::direction(4.0, 3.0, -1.0));
+        assert_eq!(a + b, b + a);
+        assert_eq!(a + b, Vector([5.0, 1.0, -0.5, 1.0]));
+        assert_eq!(a * 2.0, Vector([2.0, -4.0, 1.0, 2.0]));
+        assert_eq!(a - b + b, a);
+    }
+
+    #[test]
+    fn perspective_maps_the_near_and_far_planes_to_the_ends_of_the_depth_range() {
+        let projection = Perspective::new(FOV_Y, 1.0, NEAR, FAR);
+        assert!((projection.depth(&Vector::point(0.0, 0.0, -NEAR)) + 1.0).abs() < EPS);
+        assert!((projection.depth(&Vector::point(0.3, -0.2, -NEAR)) + 1.0).abs() < EPS);
+        assert!((projection.depth(&Vector::point(0.0, 0.0, -FAR)) - 1.0).abs() < 1e-3);
+        // w is the distance in front of the camera.
+        assert_eq!((perspective(FOV_Y, 1.0, NEAR, FAR) * Vector::point(0.0, 0.0, -NEAR)).0[3], NEAR);
+    }
+
+    #[test]
+    fn orthographic_ignores_how_far_away_points_are() {
+        let projection = Orthographic::new(-2.0, 2.0, -2.0, 2.0, NEAR, FAR);
+        for z in [-0.5, -3.0, -40.0] {
+            let [x, y] = projection.project(&Vector::point(1.0, -0.5, z));
+            assert!((x - 0.5).abs() < EPS && (y + 0.25).abs() < EPS, "z = {}", z);
+        }
+        // So the near and far ends of parallel edges stay parallel on the screen.
+        let edge = |x: f32| [projection.project(&Vector::point(x, 1.0, -1.0)), projection.project(&Vector::point(x, 1.0, -5.0))];
+        let ([a0, a1], [b0, b1]) = (edge(-1.0), edge(1.0));
+        assert_eq!([a1[0] - a0[0], a1[1] - a0[1]], [b1[0] - b0[0], b1[1] - b0[1]]);
+    }
+
+    #[test]
+    fn lines_off_the_screen_are_skipped_without_panicking() {
+        let mut frame = Framebuffer::new(20, 10);
+        draw_line(&mut frame, [-50.0, -30.0, 0.0], [-5.0, -1.0, 0.0], LineStyle::Solid, None);
+        draw_line(&mut frame, [25.0, 0.0, 0.0], [90.0, 40.0, 0.0], LineStyle::Solid, None);
+        assert_eq!(drawn_cells(&frame), 0);
+        // A line that goes right across the screen is only drawn where it's on it.
+        draw_line(&mut frame, [-100.0, 5.5, 0.0], [100.0, 5.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(drawn_cells(&frame), 20);
+    }
+
+    #[test]
+    fn clip_line_trims_lines_to_the_screen() {
+        let (inside, other) = ([2.0, 3.0, 0.5], [8.0, 7.0, 0.0]);
+        assert_eq!(clip_line(inside, other, 20.0, 10.0), Some((inside, other)));
+        assert_eq!(clip_line([-5.0, 1.0, 0.0], [-1.0, 9.0, 0.0], 20.0, 10.0), None);
+        assert_eq!(clip_line([25.0, -3.0, 0.0], [30.0, 20.0, 0.0], 20.0, 10.0), None);
+        // Crossing one edge moves that end onto it, with the depth in between.
+        assert_eq!(clip_line([-10.0, 5.0, 0.0], [10.0, 5.0, 1.0], 20.0, 10.0), Some(([0.0, 5.0, 0.5], [10.0, 5.0, 1.0])));
+        // Crossing two edges moves both ends, keeping the slope.
+        assert_eq!(clip_line([-5.0, -5.0, 0.0], [15.0, 15.0, 0.0], 20.0, 10.0), Some(([0.0, 0.0, 0.0], [10.0, 10.0, 0.0])));
+    }
+
+    #[test]
+    fn cube_across_the_near_plane_is_drawn_without_nan() {
+        let (a, b) = (Vector::point(0.0, 0.0, 1.0), Vector::point(0.0, 0.0, -3.0));
+        let (start, end) = clip_near(a, b, NEAR).unwrap();
+        assert!(approx_eq_vector(&start, &Vector::point(0.0, 0.0, -NEAR), EPS) && end == b);
+        assert_eq!(clip_near(a, Vector::point(1.0, 0.0, 0.0), NEAR), None);
+        // The front face of the cube is between the camera and the near plane, and the back face is beyond it.
+        let scene = Scene { camera: Camera::at_distance(1.05), ..cube_scene(Options { cull: false, ..Options::default() }) };
+        let frame = scene.render(&rotate_y(0.3), 80, 40);
+        assert!(frame.z_buffer.iter().all(|depth| !depth.is_nan()));
+        assert!(drawn_cells(&frame) > 0);
+    }
+
+    #[test]
+    fn set_and_get_round_trip_inside_the_framebuffer_only() {
+        let mut frame = Framebuffer::new(4, 3);
+        frame.set(3, 2, '#');
+        frame.set(0, 1, '@');
+        assert_eq!(frame.get(3, 2), Some('#'));
+        assert_eq!(frame.get(0, 1), Some('@'));
+        assert_eq!(frame.get(1, 1), Some(' '));
+        frame.set(4, 0, 'x');
+        frame.set(0, 3, 'x');
+        assert_eq!(frame.get(4, 0), None);
+        assert_eq!(frame.get(0, 3), None);
+        assert_eq!(drawn_cells(&frame), 2);
+    }
+
+    #[test]
+    fn frame_duration_clamps_the_frame_rate() {
+        assert_eq!(frame_duration(MIN_FPS), std::time::Duration::from_millis(1000));
+        assert_eq!(frame_duration(0), frame_duration(MIN_FPS));
+        assert_eq!(frame_duration(MAX_FPS), std::time::Duration::from_millis(8));
+        assert_eq!(frame_duration(MAX_FPS + 1), frame_duration(MAX_FPS));
+        assert_eq!(frame_duration(u32::MAX), frame_duration(MAX_FPS));
+        assert_eq!(frame_duration(DEFAULT_FPS), std::time::Duration::from_millis(30));
+    }
+
+    #[test]
+    fn angle_at_is_the_speed_times_the_time() {
+        assert_eq!(angle_at(std::time::Duration::ZERO, 2.0), 0.0);
+        assert_eq!(angle_at(std::time::Duration::from_secs(3), 0.5), 1.5);
+        assert!((angle_at(std::time::Duration::from_millis(250), DEFAULT_SPEED) - 0.25 * DEFAULT_SPEED).abs() < EPS);
+    }
+
+    #[test]
+    fn ansi_color_is_a_select_graphic_rendition_sequence() {
+        for color in FACE_COLORS.into_iter().chain([NO_COLOR]) {
+            let sequence = ansi_color(color);
+            let code = sequence.strip_prefix("\x1b[").and_then(|rest| rest.strip_suffix('m'));
+            assert_eq!(code, Some(color.to_string().as_str()));
+        }
+        assert_eq!(ansi_color(31), "\x1b[31m");
+    }
+
+    #[test]
+    fn fill_face_fills_the_cells_inside_a_square() {
+        let mut frame = Framebuffer::new(8, 7);
+        fill_face(&mut frame, &[[2.0, 2.0, 0.0], [6.0, 2.0, 0.0], [6.0, 5.0, 0.0], [2.0, 5.0, 0.0]], '#');
+        assert_eq!(frame.render_to_string(), "        \n        \n  ####  \n  ####  \n  ####  \n        \n        \n");
+    }
+
+    #[test]
+    fn lines_have_no_gaps_at_any_slope() {
+        for (dx, dy) in [(15i64, 0i64), (15, 4), (12, 12), (5, 14), (0, 9), (-13, 7), (-6, -11)] {
+            let mut frame = Framebuffer::new(40, 40);
+            let start = [20.5, 20.5, 0.0];
+            draw_line(&mut frame, start, [start[0] + dx as f32, start[1] + dy as f32, 0.0], LineStyle::Solid, None);
+            let mut cells: Vec<(i64, i64)> = (0..40).flat_map(|y| (0..40).map(move |x| (x, y))).filter(|&(x, y)| frame.get(x as usize, y as usize) != Some(' ')).collect();
+            // One cell for each step along the longer side, with every cell touching the one before it.
+            assert_eq!(cells.len() as i64, dx.abs().max(dy.abs()) + 1, "slope {}/{}", dy, dx);
+            if dx.abs() >= dy.abs() {
+                cells.sort();
+            } else {
+                cells.sort_by_key(|&(x, y)| (y, x));
+            }
+            assert!(cells.windows(2).all(|pair| (pair[1].0 - pair[0].0).abs() <= 1 && (pair[1].1 - pair[0].1).abs() <= 1), "slope {}/{}", dy, dx);
+        }
+    }
+
+    #[test]
+    fn line_up_to_the_right_is_drawn_with_slashes() {
+        let mut frame = Framebuffer::new(6, 6);
+        draw_line(&mut frame, [0.5, 5.5, 0.0], [5.5, 0.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(frame.render_to_string(), "     /\n    / \n   /  \n  /   \n /    \n/     \n");
+        assert_eq!(line_char(4, 4), '\\');
+    }
+
+    #[test]
+    fn default_camera_moves_the_world_back_by_the_camera_distance() {
+        // Before there was a camera, the cube was drawn by moving it CAMERA_DISTANCE away down the z axis.
+        assert!(approx_eq_matrix(&Camera::default().view_matrix(), &translation(0.0, 0.0, -CAMERA_DISTANCE), EPS));
+        let (vertices, faces) = shapes::cube();
+        let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+        let moved = Object::new(vertices, faces, Vec::new(), translation(0.0, 0.0, CAMERA_DISTANCE - 5.0));
+        let far = Scene { camera: Camera::at_distance(5.0), ..cube_scene(Options::default()) };
+        let moved = Scene { camera: Camera::at_distance(CAMERA_DISTANCE), objects: vec![moved], ..cube_scene(Options::default()) };
+        assert_eq!(far.render_frame(7, 80, 40).render_to_string(), moved.render_frame(7, 80, 40).render_to_string());
+    }
+
+    #[test]
+    fn look_at_puts_the_target_straight_in_front_of_the_camera() {
+        let (eye, target) = (Vector::point(3.0, 2.0, -4.0), Vector::point(-1.0, 0.5, 2.0));
+        let view = look_at(eye, target, Y_AXIS);
+        let distance = (target - eye).length();
+        assert!(approx_eq_vector(&(view * target), &Vector::point(0.0, 0.0, -distance), EPS));
+        assert!(approx_eq_vector(&(view * eye), &Vector::point(0.0, 0.0, 0.0), EPS));
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let m = translation(1.0, 2.0, 3.0) * rotate_x(0.4);
+        assert_eq!(transpose(&transpose(&m)).0, m.0);
+        assert_eq!(transpose(&Matrix::identity()).0, Matrix::identity().0);
+        assert_eq!(transpose(&m).0[0][3], m.0[3][0]);
+        assert_eq!(transpose(&m).0[1][2], m.0[2][1]);
+    }
+
+    #[test]
+    fn inverse_undoes_a_matrix_unless_it_is_singular() {
+        let m = translation(1.0, -2.0, 0.5) * rotate_y(0.9) * scale(2.0, 0.5, 3.0);
+        let inverse_m = inverse(&m).unwrap();
+        assert!(approx_eq_matrix(&(m * inverse_m), &Matrix::identity(), EPS));
+        assert!(approx_eq_matrix(&(inverse_m * m), &Matrix::identity(), EPS));
+        assert!(inverse(&scale(1.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn translation_moves_points_by_its_offset() {
+        let p = Vector::point(1.0, 2.0, 3.0);
+        assert_eq!(translation(0.5, -2.0, 4.0) * p, Vector::point(1.5, 0.0, 7.0));
+    }
+
+    #[test]
+    fn scale_stretches_each_axis() {
+        assert_eq!(scale(2.0, 2.0, 2.0) * Vector::point(1.0, -3.0, 0.5), Vector::point(2.0, -6.0, 1.0));
+        assert_eq!(scale(2.0, 1.0, 3.0) * Vector::direction(1.0, 1.0, 1.0), Vector::direction(2.0, 1.0, 3.0));
+    }
+
+    #[test]
+    fn face_towards_the_light_is_brighter_than_one_facing_away() {
+        let ramp = |c: char| FILL_RAMP.iter().position(|&r| r == c).unwrap();
+        let lit = face_shade(&LIGHT_DIR.normalize(), &LIGHT_DIR, 1.0);
+        let side = face_shade(&Y_AXIS, &LIGHT_DIR, 1.0);
+        let unlit = face_shade(&(LIGHT_DIR * -1.0).normalize(), &LIGHT_DIR, 1.0);
+        assert_eq!(lit, FILL_RAMP[FILL_RAMP.len() - 1]);
+        assert!(ramp(lit) > ramp(side) && ramp(side) > ramp(unlit));
+        assert_eq!(unlit, FILL_RAMP[1]);
+    }
+
+    #[test]
+    fn render_frame_can_be_called_for_a_fixed_number_of_frames() {
+        let args: Vec<String> = ["--frames", "3"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(parse_args(&args).frames, Some(3));
+        assert_eq!(parse_args(&[]).frames, None);
+        let scene = cube_scene(Options::default());
+        let frames: Vec<String> = [0, 20, 40].iter().map(|&n| scene.render_frame(n, 30, 12).render_to_string()).collect();
+        assert!(frames.iter().all(|frame| frame.lines().count() == 12));
+        assert!(frames[0] != frames[1] && frames[1] != frames[2]);
+        assert_eq!(frames[1], scene.render_frame(20, 30, 12).render_to_string());
+    }
+
+    #[test]
+    fn objects_moved_apart_are_drawn_apart() {
+        let (vertices, faces) = shapes::cube();
+        let faces: Vec<Vec<u32>> = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+        let object = |x: f32| Object::new(vertices.clone(), faces.clone(), Vec::new(), translation(x, 0.0, 0.0));
+        let bounds = |objects: Vec<Object>| {
+            let frame = Scene { camera: Camera::at_distance(8.0), objects, ..cube_scene(Options::default()) }.render(&rotate_y(0.5), 80, 40);
+            let columns: Vec<usize> = (0..80).filter(|&x| (0..40).any(|y| frame.get(x, y) != Some(' '))).collect();
+            (columns[0], columns[columns.len() - 1], drawn_cells(&frame))
+        };
+        let (left, right, both) = (bounds(vec![object(-2.5)]), bounds(vec![object(2.5)]), bounds(vec![object(-2.5), object(2.5)]));
+        assert!(left.1 < right.0, "{:?} {:?}", left, right);
+        assert_eq!((both.0, both.1, both.2), (left.0, right.1, left.2 + right.2));
+    }
+
+    #[test]
+    fn shallow_anti_aliased_line_is_a_gradient() {
+        let mut frame = Framebuffer::new(20, 6);
+        draw_line_aa(&mut frame, [0.5, 1.5, 0.0], [19.5, 4.5, 0.0]);
+        let ramp = |x: usize, y: usize| FILL_RAMP.iter().position(|&c| Some(c) == frame.get(x, y)).unwrap();
+        // Where the line passes between two rows, both of them are partly covered.
+        assert!((0..20).any(|x| (0..5).any(|y| ramp(x, y) > 0 && ramp(x, y + 1) > 0)));
+        let mut levels: Vec<usize> = (0..20).flat_map(|x| (0..6).map(move |y| (x, y))).map(|(x, y)| ramp(x, y)).filter(|&level| level > 0).collect();
+        levels.sort();
+        levels.dedup();
+        assert!(levels.len() >= 4, "{:?}", levels);
+        // Each column is covered about once in total, so no column is all dense characters.
+        assert!((0..20).all(|x| (0..6).map(|y| ramp(x, y)).sum::<usize>() <= FILL_RAMP.len()));
+    }
+
+    #[test]
+    fn box_drawing_lines_meet_in_a_corner() {
+        let mut frame = Framebuffer::new(6, 5);
+        frame.box_drawing = true;
+        draw_line(&mut frame, [1.5, 1.5, 0.0], [5.5, 1.5, 0.0], LineStyle::Solid, None);
+        draw_line(&mut frame, [1.5, 1.5, 0.0], [1.5, 4.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(frame.render_to_string(), "      \n ┌───╴\n │    \n │    \n ╵    \n");
+        // A third line through the corner turns it into a junction.
+        draw_line(&mut frame, [0.5, 1.5, 0.0], [1.5, 1.5, 0.0], LineStyle::Solid, None);
+        assert_eq!(frame.get(1, 1), Some('┬'));
+    }
+
+    #[test]
+    fn without_culling_every_face_draws_its_edges() {
+        let (vertices, faces) = shapes::cube();
+        // This turns a corner of the cube towards the camera, so three faces face it and three face away.
+        let rotation = isometric();
+        let drawn = |face: &[u8], cull: bool| {
+            let object = Object::new(vertices.clone(), vec![face.iter().map(|&v| v as u32).collect()], Vec::new(), Matrix::identity());
+            let scene = Scene { objects: vec![object], ..cube_scene(Options { cull, ..Options::default() }) };
+            drawn_cells(&scene.render(&rotation, 80, 40)) > 0
+        };
+        assert!(faces.iter().all(|face| drawn(face, false)));
+        assert_eq!(faces.iter().filter(|face| drawn(face, true)).count(), 3);
+    }
+
+    #[test]
+    fn cube_has_12_unique_edges() {
+        let faces: Vec<Vec<u32>> = FACES.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+        let edges = unique_edges(&faces);
+        assert_eq!(edges.len(), 12);
+        // Every edge of a closed mesh is shared by exactly two faces.
+        assert!(edges.iter().all(|edge| edge.faces.len() == 2 && edge.ends[0] < edge.ends[1]));
+        assert_eq!(super::edges(&faces).count(), 12);
+    }
+
+    #[test]
+    fn square_spans_twice_as_many_columns_as_rows_with_cells_twice_as_tall() {
+        for (width, height) in [(80, 40), (120, 30)] {
+            let projection = Perspective::new(FOV_Y, screen_aspect(width, height, 2.0), NEAR, FAR);
+            let [x0, y0, _] = project(&projection, &Vector::point(-0.5, -0.5, -3.0), width, height);
+            let [x1, y1, _] = project(&projection, &Vector::point(0.5, 0.5, -3.0), width, height);
+            assert!(((x1 - x0) - 2.0 * (y0 - y1)).abs() < 1e-3, "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn farthest_face_is_drawn_first() {
+        let faces: Vec<Vec<u32>> = FACES.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+        let to_view = Camera::default().view_matrix() * rotate_y(0.3) * rotate_x(0.2);
+        let view_pos: Vec<Vector> = VERTICES.iter().map(|&v| to_view * v).collect();
+        let order = sort_faces_by_depth(&faces, &view_pos);
+        // The back face has all the corners with a z of -1.0, which end up farthest from the camera.
+        assert_eq!(order[0], 3);
+        assert_eq!(order[5], 0);
+        assert!(order.windows(2).all(|pair| face_depth(&faces[pair[0]], &view_pos) <= face_depth(&faces[pair[1]], &view_pos)));
+    }
+
+    #[test]
+    fn zero_length_line_draws_nothing() {
+        let mut frame = Framebuffer::new(5, 5);
+        draw_line(&mut frame, [2.5, 2.5, 0.0], [2.5, 2.5, 0.0], LineStyle::Solid, None);
+        draw_line_aa(&mut frame, [1.5, 3.5, 0.0], [1.5, 3.5, 0.0]);
+        assert_eq!(drawn_cells(&frame), 0);
+        // Nor do lines with an end that couldn't be projected.
+        draw_line(&mut frame, [2.5, 2.5, 0.0], [f32::NAN, 1.0, 0.0], LineStyle::Solid, None);
+        draw_line(&mut frame, [2.5, 2.5, 0.0], [f32::INFINITY, 1.0, 0.0], LineStyle::Solid, None);
+        assert_eq!(drawn_cells(&frame), 0);
+    }
+
+    #[test]
+    fn translation_moves_points_but_not_directions() {
+        let m = translation(2.0, -1.0, 5.0);
+        assert_eq!(m * Vector::direction(1.0, 2.0, 3.0), Vector::direction(1.0, 2.0, 3.0));
+        assert_eq!(m * Vector::point(1.0, 2.0, 3.0), Vector::point(3.0, 1.0, 8.0));
+    }
+
+    #[test]
+    fn perspective_divide_puts_points_in_view_inside_the_ndc_range() {
+        let m = perspective(FOV_Y, 2.0, NEAR, FAR);
+        for v in [Vector::point(0.0, 0.0, -1.0), Vector::point(1.9, -0.9, -1.0), Vector::point(-30.0, 20.0, -50.0), Vector::point(0.1, 0.1, -99.0)] {
+            let ndc = perspective_divide(&(m * v));
+            assert!(ndc.iter().all(|value| (-1.0..=1.0).contains(value)), "{:?} -> {:?}", v, ndc);
+        }
+        // A point beyond the edge of the view ends up outside it.
+        assert!(perspective_divide(&(m * Vector::point(2.1, 0.0, -1.0)))[0] > 1.0);
+    }
+
+    #[test]
+    fn ndc_corners_map_to_the_screen_corners() {
+        assert_eq!(ndc_to_screen([-1.0, 1.0], 80, 40), [0.0, 0.0]);
+        assert_eq!(ndc_to_screen([1.0, 1.0], 80, 40), [80.0, 0.0]);
+        assert_eq!(ndc_to_screen([-1.0, -1.0], 80, 40), [0.0, 40.0]);
+        assert_eq!(ndc_to_screen([1.0, -1.0], 80, 40), [80.0, 40.0]);
+        assert_eq!(ndc_to_screen([0.0, 0.0], 31, 9), [15.5, 4.5]);
+    }
+
+    #[test]
+    fn thickness_2_draws_twice_as_many_cells() {
+        let cells = |thickness: usize| {
+            let mut frame = Framebuffer::new(20, 10);
+            frame.thickness = thickness;
+            draw_line(&mut frame, [2.5, 4.5, 0.0], [17.5, 4.5, 0.0], LineStyle::Solid, None);
+            drawn_cells(&frame)
+        };
+        assert_eq!(cells(1), 16);
+        assert_eq!(cells(2), 2 * cells(1));
+        assert_eq!(cells(0), cells(1));
+    }
+
+    #[test]
+    fn depth_cue_makes_nearer_cells_brighter() {
+        let mut frame = Framebuffer::new(3, 1);
+        frame.set_with_depth(0, 0, 0.8, '-');
+        frame.set_with_depth(1, 0, 0.2, '-');
+        frame.set_with_depth(2, 0, 0.5, '-');
+        frame.depth_cue();
+        let ramp = |x: usize| FILL_RAMP.iter().position(|&c| Some(c) == frame.get(x, 0)).unwrap();
+        assert!(ramp(1) > ramp(2) && ramp(2) > ramp(0));
+        assert_eq!(frame.get(1, 0), Some(FILL_RAMP[FILL_RAMP.len() - 1]));
+    }
+
+    #[test]
+    fn single_quad_has_4_edges() {
+        assert_eq!(edges(&[vec![4, 7, 1, 2]]).collect::<Vec<_>>(), vec![(2, 4), (4, 7), (1, 7), (1, 2)]);
+    }
+
+    #[test]
+    fn frame_stats_average_the_frames_added() {
+        let ms = std::time::Duration::from_millis;
+        let mut stats = FrameStats::default();
+        assert_eq!(stats.average(), None);
+        stats.add(ms(10), ms(2));
+        stats.add(ms(20), ms(4));
+        stats.add(ms(30), ms(6));
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.average(), Some((ms(20), ms(4))));
+    }
+
+    #[test]
+    fn nan_and_negative_positions_are_not_drawn() {
+        let mut frame = Framebuffer::new(4, 4);
+        for [x, y] in [[f32::NAN, 1.5], [1.5, f32::NAN], [-0.5, 1.5], [1.5, -3.0], [f32::NEG_INFINITY, 0.0], [4.0, 1.0]] {
+            plot(&mut frame, x, y, 0.0, '#');
+        }
+        fill_face(&mut frame, &[[f32::NAN, 0.0, 0.0], [3.0, 0.0, 0.0], [3.0, 3.0, 0.0]], '#');
+        fill_triangle(&mut frame, [f32::NAN, 0.0, 0.0], [3.0, 0.0, 0.0], [3.0, 3.0, 0.0], '#');
+        assert_eq!(drawn_cells(&frame), 0);
+        assert_eq!(cell(-0.5, 1.0, 4, 4), None);
+        assert_eq!(cell(0.5, 3.9, 4, 4), Some((0, 3)));
+    }
+
+    #[test]
+    fn rotate_axis_around_z_is_rotate_z() {
+        for angle in [0.3, -1.2, 2.5] {
+            assert!(approx_eq_matrix(&rotate_axis(Z_AXIS, angle), &rotate_z(angle), EPS));
+            // The axis is normalized, so its length doesn't matter.
+            assert!(approx_eq_matrix(&rotate_axis(Vector::direction(0.0, 0.0, 4.0), angle), &rotate_z(angle), EPS));
+        }
+    }
+
+    #[test]
+    fn same_angle_draws_the_same_frame() {
+        let scene = cube_scene(Options::default());
+        let first = scene.render_angle(0.7, 80, 40);
+        let second = scene.render_angle(0.7, 80, 40);
+        assert_eq!(first.cells, second.cells);
+        assert_eq!(first.colors, second.colors);
+        assert_ne!(scene.render_angle(0.8, 80, 40).cells, first.cells);
+    }
+
+    #[test]
+    fn mul_operators_match_the_multiply_functions() {
+        let (a, b) = (rotate_x(0.5) * translation(1.0, 0.0, -2.0), scale(1.0, 2.0, 0.5) * rotate_z(0.3));
+        let v = Vector::point(0.5, -1.0, 2.0);
+        assert_eq!((a * b).0, matrix_times_matrix(&a, &b).0);
+        assert_eq!(a * v, matrix_times_vector(&a, &v));
+        // b is applied first.
+        assert!(approx_eq_vector(&(a * b * v), &(a * (b * v)), EPS));
+    }
+
+    #[test]
+    fn line_characters_can_be_changed() {
+        let args: Vec<String> = ["--vchar", "#", "--hchar", "#"].iter().map(|arg| arg.to_string()).collect();
+        let options = parse_args(&args);
+        assert_eq!((options.vertical_char, options.horizontal_char), ('#', '#'));
+        // Frame 0 looks straight at the front face, so its edges are all straight up or across.
+        let frame = cube_scene(options).render_frame(0, 80, 40);
+        assert!(drawn_cells(&frame) > 0);
+        assert!(frame.cells.iter().all(|&c| c == ' ' || c == '#'));
+        assert!(parse_line_char("##").is_err() && parse_line_char(" ").is_err());
+    }
+
+    #[test]
+    fn unchanged_transform_reuses_the_cached_positions() {
+        let object = &cube_scene(Options::default()).objects[0];
+        let (to_view, projection) = (translation(0.0, 0.0, -3.0) * rotate_y(0.4), Perspective::new(FOV_Y, 1.0, NEAR, FAR));
+        let first = object.transform(&to_view, &projection, 80, 40).screen_pos.clone();
+        // Marking the cache shows whether the next call works the positions out again or not.
+        object.cache.borrow_mut().screen_pos[0] = [-1.0; 3];
+        assert_eq!(object.transform(&to_view, &projection, 80, 40).screen_pos[0], [-1.0; 3]);
+        assert_eq!(object.transform(&(to_view * rotate_x(0.1)), &projection, 80, 40).screen_pos.len(), first.len());
+        assert_eq!(object.transform(&to_view, &projection, 80, 40).screen_pos, first);
+        assert_ne!(object.transform(&to_view, &projection, 60, 40).screen_pos, first);
+    }
+
+    #[test]
+    fn spinning_around_x_moves_vertices_in_the_y_z_plane() {
+        let args: Vec<String> = ["--spin-axis", "x"].iter().map(|arg| arg.to_string()).collect();
+        let spin_axis = parse_args(&args).spin_axis;
+        assert_eq!(spin_axis, SpinAxis::X);
+        for v in VERTICES {
+            let moved = spin_axis.matrix(0.6) * v;
+            assert_eq!(moved.0[0], v.0[0]);
+            assert!((moved.0[1] - v.0[1]).abs() > 0.1 && (moved.0[2] - v.0[2]).abs() > 0.1);
+            assert!((moved.0[1].hypot(moved.0[2]) - v.0[1].hypot(v.0[2])).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn face_centroid_is_the_middle_of_a_square() {
+        let square = [[2.0, 1.0, 0.2], [6.0, 1.0, 0.2], [6.0, 5.0, 0.6], [2.0, 5.0, 0.6]];
+        let [x, y, depth] = face_centroid(&square);
+        assert_eq!([x, y], [4.0, 3.0]);
+        assert!((depth - 0.4).abs() < EPS);
+    }
+
+    #[test]
+    fn points_marks_every_vertex_on_the_screen() {
+        let scene = cube_scene(Options { points: true, cull: false, ..Options::default() });
+        let frame = scene.render(&(rotate_x(0.4) * rotate_y(0.6)), 80, 40);
+        let cache = scene.objects[0].cache.borrow();
+        assert_eq!(cache.screen_pos.len(), 8);
+        for &[x, y, _] in &cache.screen_pos {
+            assert_eq!(frame.get(x as usize, y as usize), Some(POINT_CHAR), "vertex at {}, {}", x, y);
+        }
+        assert_eq!(frame.cells.iter().filter(|&&c| c == POINT_CHAR).count(), 8);
+    }
+
+    #[test]
+    fn each_projection_puts_a_known_vertex_in_the_right_place() {
+        let v = Vector::point(1.0, 1.0, -2.0);
+        // With a 90 degree field of view the edge of the view is as far out as the point is in front.
+        let projection = Perspective::new(FOV_Y, 1.0, NEAR, FAR);
+        let [x, y] = projection.project(&v);
+        assert!((x - 0.5).abs() < EPS && (y - 0.5).abs() < EPS);
+        assert!(projection.depth(&v) > projection.depth(&Vector::point(1.0, 1.0, -1.0)));
+        let flat = Orthographic::new(-2.0, 2.0, -4.0, 4.0, NEAR, FAR);
+        assert_eq!(flat.project(&v), [0.5, 0.25]);
+        assert!(flat.depth(&v) > flat.depth(&Vector::point(1.0, 1.0, -1.0)));
+        assert_eq!(projection.matrix().0, perspective(FOV_Y, 1.0, NEAR, FAR).0);
+    }
+
+    #[test]
+    fn defaults_are_the_identity_and_the_origin() {
+        assert_eq!(Matrix::default().0, Matrix::identity().0);
+        assert_eq!(Vector::default(), Vector([0.0, 0.0, 0.0, 1.0]));
+        // The default point is moved by translations, unlike a zero direction.
+        assert_eq!(translation(1.0, 2.0, 3.0) * Vector::default(), Vector::point(1.0, 2.0, 3.0));
+        let array: [Vector; 2] = Default::default();
+        assert_eq!(array, [Vector::point(0.0, 0.0, 0.0); 2]);
+    }
+
+    #[test]
+    fn dotted_and_dashed_lines_leave_regular_gaps() {
+        let row = |style: LineStyle| {
+            let mut frame = Framebuffer::new(12, 1);
+            draw_line(&mut frame, [0.5, 0.5, 0.0], [11.5, 0.5, 0.0], style, None);
+            frame.render_to_string()
+        };
+        assert_eq!(row(LineStyle::Dotted), "- - - - - - \n");
+        assert_eq!(row(LineStyle::Dashed), "--- --- --- \n");
+        assert_eq!(row(LineStyle::Solid), "------------\n");
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_tumble() {
+        let (axis, speed) = tumble(42);
+        assert_eq!(tumble(42), (axis, speed));
+        assert_eq!(axis.matrix(angle_at(frame_duration(DEFAULT_FPS), speed)).0, tumble(42).0.matrix(angle_at(frame_duration(DEFAULT_FPS), speed)).0);
+        assert_ne!(tumble(43), (axis, speed));
+        assert!((axis.vector().length() - 1.0).abs() < EPS);
+        assert!(TUMBLE_SPEEDS.contains(&speed));
+    }
+
+    #[test]
+    fn gamma_1_is_linear_and_gamma_2_darkens_the_midtones() {
+        let ramp = |c: char| FILL_RAMP.iter().position(|&r| r == c).unwrap();
+        // This normal is at 60 degrees to the light, so its brightness is 0.5.
+        let light = Vector::direction(0.0, 0.0, 1.0);
+        let normal = Vector::direction(0.0, 0.75f32.sqrt(), 0.5);
+        assert_eq!(ramp(face_shade(&normal, &light, 1.0)), ramp_index(0.5));
+        assert_eq!(ramp(face_shade(&normal, &light, 2.0)), ramp_index(0.25));
+        assert!(ramp(face_shade(&normal, &light, 2.0)) < ramp(face_shade(&normal, &light, 1.0)));
+        // The ends of the ramp stay where they are.
+        assert_eq!(face_shade(&light, &light, 2.0), face_shade(&light, &light, 1.0));
+    }
+
+    #[test]
+    fn isometric_top_face_is_a_symmetric_rhombus() {
+        let projection = Orthographic::new(-ORTHO_SIZE, ORTHO_SIZE, -ORTHO_SIZE, ORTHO_SIZE, NEAR, FAR);
+        let to_view = Camera::default().view_matrix() * isometric();
+        // The top face is the one with every corner at a y of 1.0.
+        let corners: Vec<[f32; 2]> = FACES[5].iter().map(|&v| projection.project(&(to_view * VERTICES[v as usize]))).collect();
+        let side = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).hypot(a[1] - b[1]);
+        for i in 0..4 {
+            assert!((side(corners[i], corners[(i + 1) % 4]) - side(corners[0], corners[1])).abs() < EPS);
+        }
+        // Its diagonals are level and upright, and cross in the middle, with the level one sqrt(3) times as long.
+        let (across, up) = ((corners[0], corners[2]), (corners[1], corners[3]));
+        let (across, up) = if (across.0[1] - across.1[1]).abs() < EPS { (across, up) } else { (up, across) };
+        assert!((across.0[1] - across.1[1]).abs() < EPS && (up.0[0] - up.1[0]).abs() < EPS);
+        assert!((across.0[0] + across.1[0]).abs() < EPS && (up.0[0] - (across.0[0] + across.1[0]) / 2.0).abs() < EPS);
+        assert!((side(across.0, across.1) / side(up.0, up.1) - 3.0f32.sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn face_area_of_a_unit_square_and_a_line() {
+        assert_eq!(face_area(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]), 1.0);
+        assert_eq!(face_area(&[[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]]), 1.0);
+        assert_eq!(face_area(&[[0.0, 0.0], [1.0, 1.0], [3.0, 3.0]]), 0.0);
+    }
+
+    #[test]
+    fn min_area_culls_a_collapsed_face() {
+        // This face has been squashed flat, so on the screen it is only a line.
+        let vertices = vec![Vector::point(-1.0, 0.0, 0.0), Vector::point(1.0, 0.0, 0.0), Vector::point(1.0, 0.001, 0.0), Vector::point(-1.0, 0.001, 0.0)];
+        let object = Object::new(vertices, vec![vec![0, 3, 2, 1]], Vec::new(), Matrix::identity());
+        let drawn = |min_area: f32| {
+            let scene = Scene { objects: vec![object.clone()], ..cube_scene(Options { cull: false, min_area, ..Options::default() }) };
+            drawn_cells(&scene.render(&Matrix::identity(), 80, 40))
+        };
+        assert!(drawn(0.0) > 0);
+        assert_eq!(drawn(1.0), 0);
+    }
+
+    #[test]
+    fn homogenize_divides_by_w() {
+        assert_eq!(Vector([2.0, -4.0, 6.0, 2.0]).homogenize(), Vector::point(1.0, -2.0, 3.0));
+        assert_eq!(Vector::direction(1.0, 2.0, 3.0).homogenize(), Vector::direction(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn farther_cameras_draw_smaller_cubes() {
+        let extent = |distance: f32| {
+            let scene = Scene { camera: Camera::at_distance(distance), ..cube_scene(Options::default()) };
+            let frame = scene.render(&rotate_y(0.4), 80, 40);
+            let columns: Vec<usize> = (0..80).filter(|&x| (0..40).any(|y| frame.get(x, y) != Some(' '))).collect();
+            columns[columns.len() - 1] - columns[0]
+        };
+        assert!(extent(3.0) > extent(5.0) && extent(5.0) > extent(10.0));
+        assert_eq!(Camera::at_distance(0.0).position, Vector::point(0.0, 0.0, MIN_CAMERA_DISTANCE));
+    }
+
+    #[test]
+    fn mirror_negates_the_height_above_the_floor() {
+        for v in VERTICES.iter().chain(&[Vector::point(0.3, 2.5, -0.7)]) {
+            let reflected = mirror_matrix() * *v;
+            assert!((reflected.0[1] - shapes::FLOOR_Y + (v.0[1] - shapes::FLOOR_Y)).abs() < EPS);
+            assert_eq!([reflected.0[0], reflected.0[2], reflected.0[3]], [v.0[0], v.0[2], v.0[3]]);
+        }
+        // The floor is at -1.0, so the top of the cube ends up at -3.0.
+        assert_eq!((mirror_matrix() * Vector::point(0.0, 1.0, 0.0)).0[1], -3.0);
+    }
+
+    #[test]
+    fn faded_line_has_the_average_intensity_in_the_middle() {
+        let mut frame = Framebuffer::new(11, 1);
+        draw_line(&mut frame, [0.0, 0.5, 0.0], [11.0, 0.5, 0.0], LineStyle::Solid, Some([0.2, 0.8]));
+        assert_eq!(frame.get(5, 0), Some(FILL_RAMP[ramp_index(0.5)]));
+        assert_eq!(frame.get(0, 0), Some(FILL_RAMP[ramp_index(0.2)]));
+        assert_eq!(frame.get(10, 0), Some(FILL_RAMP[ramp_index(0.8)]));
+    }
+
+    #[test]
+    fn loop_period_brings_frame_n_back_to_frame_0() {
+        assert_eq!(loop_angle(0, 48), 0.0);
+        assert_eq!(loop_angle(48, 48), loop_angle(0, 48));
+        assert_eq!(loop_angle(12, 48), std::f32::consts::FRAC_PI_2);
+        let scene = cube_scene(Options { loop_period: Some(48), ..Options::default() });
+        assert_eq!(scene.render_frame(48, 80, 40).render_to_string(), scene.render_frame(0, 80, 40).render_to_string());
+        assert_eq!(scene.render_frame(50, 80, 40).render_to_string(), scene.render_frame(2, 80, 40).render_to_string());
+    }
+
+    #[test]
+    fn vector_from_a_slice_needs_exactly_four_values() {
+        let values = [1.0, 2.0, 3.0, 1.0, 5.0];
+        assert!(Vector::try_from(&values[..3]).is_err());
+        assert_eq!(Vector::try_from(&values[..4]).unwrap(), Vector::point(1.0, 2.0, 3.0));
+        assert!(Vector::try_from(&values[..]).is_err());
+    }
+
+    #[test]
+    fn width_and_height_fall_back_to_the_default_size_unless_valid() {
+        let size = |args: &[&str]| {
+            let options = parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>());
+            (options.width, options.height)
+        };
+        assert_eq!(size(&["--width", "120", "--height", "30"]), (120, 30));
+        assert_eq!(size(&["--width"]), (SCREEN_WIDTH, SCREEN_HEIGHT));
+        assert_eq!(size(&["--width", "abc", "--height", "-5"]), (SCREEN_WIDTH, SCREEN_HEIGHT));
+        assert_eq!(size(&["--width", "0"]), (SCREEN_WIDTH, SCREEN_HEIGHT));
+        assert_eq!((SCREEN_WIDTH, SCREEN_HEIGHT), (80, 40));
+    }
+
+    #[test]
+    fn with_context_keeps_the_kind_and_starts_with_the_path() {
+        let error = with_context(std::io::Error::new(std::io::ErrorKind::NotFound, "no such file"), "cube.obj");
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(error.to_string(), "cube.obj: no such file");
+    }
+}
@@ -1,164 +1,202 @@
-//! A spinning text cube
-//! 
-//! 
-//! 4    +------+  6
-//!     /|     /| 
-//! 5  +------+ |  7
-//!    | |    | | 
-//! 0  | +----|-+  2
-//!    |/     |/   
-//! 1  +------+    3
-
-///This code defines a new struct called Matrix that consists of a fixed-size array of 4 arrays of 4 f32 values. 
-///The #[derive(Debug, Clone, Copy)] line is called an attribute.
-///it tells the Rust compiler to automatically implement the Debug, Clone, and Copy traits for the Matrix struct.
-#[derive(Debug, Clone, Copy)]
-struct Matrix([[f32; 4]; 4]);
-
-///This code defines a new struct called Vector that consists of a fixed-size array of 4 f32 values.
-#[derive(Debug, Clone, Copy)]
-struct Vector([f32; 4]);
-
-///These are the vertices of a cube. Each vertex is represented as a Vector struct, 
-///which contains an array of four f32 values. The first three values represent the x, y, 
-///and z coordinates of the vertex in 3D space, and the fourth value is a homogeneous coordinate. 
-///The vertices are defined in local space, meaning that they represent the positions of the points relative to the center of the cube.
-const VERTICES : [Vector; 8] = [
-    Vector([-1.0, -1.0, -1.0, 1.0]),
-    Vector([-1.0, -1.0,  1.0, 1.0]),
-    Vector([ 1.0, -1.0, -1.0, 1.0]),
-    Vector([ 1.0, -1.0,  1.0, 1.0]),
-    Vector([-1.0,  1.0, -1.0, 1.0]),
-    Vector([-1.0,  1.0,  1.0, 1.0]),
-    Vector([ 1.0,  1.0, -1.0, 1.0]),
-    Vector([ 1.0,  1.0,  1.0, 1.0]),
-];
-
-//This code defines a constant array of 6 arrays of 4 u8 values called FACES. 
-///Each inner array represents the indices of the vertices of a face of a cube in 3D space. 
-///For example, the first inner array [1, 5, 7, 3] represents the vertices with indices 1, 5, 7, and 3 in a list of vertices.
-const FACES : [[u8; 4]; 6] = [
-    [1, 5, 7, 3],
-    [3, 7, 6, 2],
-    [0, 4, 5, 1],
-    [2, 6, 4, 0],
-    [0, 1, 3, 2],
-    [5, 4, 6, 7],
-];
-
-
-///This function, matrix_times_vector, calculates the product of a matrix and a vector.
-///The function starts by destructuring the matrix and vector into their individual components. 
-///The matrix is destructured into 4 [f32; 4] arrays, and the vector is destructured into an array of 4 f32 values.
-///The function then calculates the product of the matrix and the vector by taking the weighted sum of the columns of the matrix.
-fn matrix_times_vector(m: &Matrix, v: &Vector) -> Vector {
-    let [mx, my, mz, mw] = &m.0;
-    let [x, y, z, w] = v.0;
-    // The product is the weighted sum of the columns.
-    Vector([
-        x * mx[0] + y * my[0] + z * mz[0] + w * mw[0],
-        x * mx[1] + y * my[1] + z * mz[1] + w * mw[1],
-        x * mx[2] + y * my[2] + z * mz[2] + w * mw[2],
-        x * mx[3] + y * my[3] + z * mz[3] + w * mw[3],
-    ])
-}
+//! The program that draws the spinning cube in the terminal. The drawing itself is done by the library.
+
+use simple_rust_cube::quaternion::Quaternion;
+use simple_rust_cube::*;
+use std::io::Write;
+
+///this is setting up a loop that will run until it is stopped, or until --frames frames have been drawn, and create a series of frames for animation.
+///The angle of the cube in each frame comes from the time since the loop started, and any keys pressed in interactive mode.
+///When a GIF is being made, each frame comes from its frame number instead, see Scene::render_frame.
+///The command line arguments are described on parse_args.
+///Errors are returned to main instead of panicking, so the terminal is put back to normal before they are printed.
+///The output going away, for example when it is piped into head, isn't an error and just stops the loop.
+fn run() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // The screen fills the terminal unless --width or --height say otherwise, and is the default size if that can't be found.
+    let defaults = match terminal::detect_terminal_size() {
+        Some((width, height)) => Options { width, height, ..Options::default() },
+        None => Options::default(),
+    };
+    let options = parse_args_with_defaults(&args, defaults.clone());
+    // Only a screen that is the size of the terminal follows it when it is resized.
+    let fit_terminal = (options.width, options.height) == (defaults.width, defaults.height);
+
+    // The terminal goes back to normal when these are dropped at the end of main.
+    // A GIF, a single frame or --raw records are made without drawing anything over the screen, so the terminal is left alone.
+    let _terminal = (options.gif.is_none() && !options.once && !options.raw).then(terminal::TerminalGuard::new);
+    let raw_mode = if options.interactive { keyboard::RawMode::enable().ok() } else { None };
+    let mut controls = keyboard::Controls::default();
+
+    let (vertices, faces, lines) = match &options.obj {
+        _ if options.stdin => {
+            let (vertices, faces) = obj::read_obj(std::io::stdin().lock()).map_err(|error| with_context(error.into(), "standard input"))?;
+            (vertices, faces, Vec::new())
+        }
+        Some(path) => {
+            let (vertices, faces) = obj::load_obj(path).map_err(|error| with_context(error.into(), path))?;
+            (vertices, faces, Vec::new())
+        }
+        None if options.shape == "sphere" => {
+            let (vertices, lines) = shapes::uv_sphere(shapes::SPHERE_RINGS, shapes::SPHERE_SEGMENTS);
+            (vertices, Vec::new(), lines)
+        }
+        None => {
+            // parse_args only accepts names that shapes::by_name knows.
+            let (vertices, faces) = shapes::by_name(&options.shape).unwrap_or_else(shapes::cube);
+            let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+            (vertices, faces, Vec::new())
+        }
+    };
+
+    if let Some(dir) = &options.export {
+        std::fs::create_dir_all(dir).map_err(|error| with_context(error, dir))?;
+    }
+
+    let frames = if options.gif.is_some() { Some(options.frames.unwrap_or(DEFAULT_GIF_FRAMES)) } else { options.frames };
+    // A single object is placed in the middle, with a model matrix that leaves it as it is.
+    let object = Object::new(vertices, faces, lines, Matrix::identity());
+    let (camera, objects) = match &options.scene {
+        Some(path) => scene::load_scene(path).map_err(|error| with_context(error.into(), path))?,
+        None => (Camera::at_distance(options.distance), grid(&object, options.count)),
+    };
+    let keyframes = match &options.keyframes {
+        Some(path) => Some(keyframes::load_keyframes(path).map_err(|error| with_context(error.into(), path))?),
+        None => None,
+    };
+    let scene = Scene { options, camera, objects };
+    let options = &scene.options;
+
+    if options.once {
+        let frame = scene.render_angle(options.angle, options.width, options.height);
+        let text = if options.color { frame.render_to_colored_string() } else { frame.render_to_string() };
+        return match std::io::stdout().write_all(text.as_bytes()) {
+            Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        };
+    }
+
+    // The automatic spin is added on to orientation a little at a time, see Quaternion.
+    let mut orientation = Quaternion::identity();
+    // With --keyframes the time along them is kept instead, which also stops while the spin is paused.
+    let mut keyframe_time = std::time::Duration::ZERO;
+    let mut last_frame = std::time::Instant::now();
+    let mut first_frame = true;
+    let mut gif_frames = Vec::new();
+    let mut stats = FrameStats::default();
+    let mut last_stats = std::time::Instant::now();
+    // The same framebuffer is cleared and drawn again every frame, instead of making a new one each time.
+    let mut size = (options.width, options.height);
+    let mut frame = Framebuffer::new(size.0, size.1);
+    // With --ss the frame is drawn bigger into samples first, which is kept the same way.
+    let mut samples = Framebuffer::new(0, 0);
+    let start = std::time::Instant::now();
+    if options.raw && options.gif.is_none() {
+        match std::io::stdout().write_all(terminal::raw_header(size.0, size.1).as_bytes()) {
+            Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+    }
+    for frame_number in 0u64.. {
+        if frames == Some(frame_number) {
+            break;
+        }
+
+        // After a resize the framebuffer is made again at the new size, and the screen is cleared so no old rows are left behind.
+        if fit_terminal && terminal::take_resized() {
+            if let Some(new_size) = terminal::new_size(size, terminal::detect_terminal_size()) {
+                size = new_size;
+                frame = Framebuffer::new(size.0, size.1);
+                first_frame = true;
+            }
+        }
 
-///The SCREEN_WIDTH and SCREEN_HEIGHT constants are of type usize and represent the width and height of the screen 
-const SCREEN_WIDTH : usize = 80;
-const SCREEN_HEIGHT : usize = 40;
-
-///The OFFSET_X and OFFSET_Y constants are of type f32 and represent the horizontal and vertical offsets of the screen from the origin in pixels.
-const OFFSET_X : f32 = SCREEN_WIDTH as f32 * 0.5;
-const OFFSET_Y : f32 = SCREEN_HEIGHT as f32 * 0.5;
-
-///The SCALE_X and SCALE_Y constants are also of type f32 and represent the horizontal and vertical scaling factors for the screen. 
-///These factors are used to stretch or shrink the screen along the x and y axes
-const SCALE_X : f32 = SCREEN_WIDTH as f32 * 0.5;
-const SCALE_Y : f32 = SCREEN_HEIGHT as f32 * 0.5;
-
-
-///this is setting up a loop that will run indefinitely and create a series of frames for animation.
-///the cube_to_world matrix is a 4x4 matrix that to be used to rotate an object around the origin in 3D.
-fn main() {
-    for frame_number in 0.. {
-        let mut frame = [[b' ';SCREEN_WIDTH]; SCREEN_HEIGHT];
-
-        let t = frame_number as f32 * 0.01;
-        let (c, s) = (t.cos(), t.sin());
-
-        let cube_to_world = Matrix([
-            // Each row is a column of a matrix.
-            [  c, 0.0,   s, 0.0],
-            [0.0, 1.0, 0.0, 0.0],
-            [ -s, 0.0,   c, 0.0],
-            [0.0, 0.0,-2.5, 1.0],
-        ]);
-
-
-        let mut screen_pos = [[0.0, 0.0]; 8];
-        for (v, s) in VERTICES.iter().zip(screen_pos.iter_mut()) {
-            let world_pos = matrix_times_vector(&cube_to_world, v);
-            let recip_z = 1.0 /  world_pos.0[2];
-            let screen_x = world_pos.0[0] * recip_z * SCALE_X + OFFSET_X;
-            let screen_y = world_pos.0[1] * recip_z * SCALE_Y + OFFSET_Y;
-            *s = [screen_x, screen_y];
-            // frame[screen_y as usize][screen_x as usize] = b'.';
+        if let Some(raw_mode) = &raw_mode {
+            for key in raw_mode.read_keys() {
+                controls.apply(key);
+            }
+        }
+        if controls.quit || terminal::interrupted() {
+            break;
         }
 
-        for face in FACES {
-            if !cull(screen_pos[face[0] as usize], screen_pos[face[1] as usize], screen_pos[face[2] as usize]) {
-                let mut end = face[3];
-                for start in face {
-                    draw_line(&mut frame, screen_pos[start as usize], screen_pos[end as usize]);
-                    end = start;
+        let render_start = std::time::Instant::now();
+        if options.gif.is_some() {
+            // The frames of a GIF are all shown for the same time, however long they took to draw.
+            match &keyframes {
+                Some(keyframes) => {
+                    let time = frame_duration(options.fps) * frame_number as u32;
+                    scene.render_into(&keyframes::rotation_at(keyframes, time.as_secs_f32(), options.easing), &mut frame, &mut samples);
                 }
+                None => scene.render_frame_into(frame_number, &mut frame, &mut samples),
             }
+        } else {
+            let now = std::time::Instant::now();
+            if !controls.paused {
+                let turn = Quaternion::from_axis_angle(&options.spin_axis.vector(), angle_at(now - last_frame, options.speed));
+                orientation = (turn * orientation).normalize();
+                keyframe_time += now - last_frame;
+            }
+            last_frame = now;
+            let tilt = Quaternion::from_axis_angle(&Y_AXIS, controls.y_angle) * Quaternion::from_axis_angle(&X_AXIS, controls.x_angle);
+            let rotation = match &keyframes {
+                Some(keyframes) => keyframes::rotation_at(keyframes, keyframe_time.as_secs_f32(), options.easing) * tilt.to_matrix(),
+                None => (orientation * tilt).to_matrix(),
+            };
+            // The rotations multiplied together above each round a little, so the result is made rigid again before it is drawn.
+            scene.render_into(&rotation.orthonormalize(), &mut frame, &mut samples);
+        }
+        let render_time = render_start.elapsed();
+
+        if let Some(dir) = &options.export {
+            let path = export::frame_path(dir, frame_number + 1);
+            export::write_ppm(&frame, &path).map_err(|error| with_context(error, &path))?;
         }
 
-        for l in 0..SCREEN_HEIGHT {
-            let row = std::str::from_utf8(&frame[l]).unwrap();
-            println!("{}", row);
+        if options.gif.is_some() {
+            gif_frames.push(frame.clone());
+            continue;
         }
 
-        print!("\x1b[{}A;", SCREEN_HEIGHT);
+        let present_start = std::time::Instant::now();
+        let shown = if options.raw {
+            terminal::write_raw_record(&mut std::io::stdout(), start.elapsed(), &frame, options.color, first_frame)
+        } else {
+            terminal::present(&mut std::io::stdout(), &frame, options.color, first_frame)
+        };
+        match shown {
+            Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => break,
+            result => result?,
+        }
+        first_frame = false;
+
+        if options.stats {
+            // The averages go to stderr, so they don't get mixed into the frames on stdout.
+            stats.add(render_time, present_start.elapsed());
+            if last_stats.elapsed() >= STATS_INTERVAL {
+                if let Some((render, present)) = stats.average() {
+                    eprintln!("{} frames: render {:.2?}, present {:.2?}", stats.frames, render, present);
+                }
+                stats = FrameStats::default();
+                last_stats = std::time::Instant::now();
+            }
+        }
 
-        std::thread::sleep(std::time::Duration::from_millis(30));
+        std::thread::sleep(frame_duration(options.fps));
     }
-}
 
-///the function cull takes three parameters: p0, p1, and p2, which are arrays of two f32 values. The function returns a boolean value.
-/// indicating whether the triangle formed by the three points is clockwise or counterclockwise.
-fn cull(p0: [f32; 2], p1: [f32; 2], p2: [f32; 2]) -> bool {
-    let dx = [p1[0] - p0[0], p2[0] - p1[0]];
-    let dy = [p1[1] - p0[1], p2[1] - p1[1]];
-    dx[0] * dy[1] > dx[1] * dy[0]
+    if let Some(path) = &options.gif {
+        export::write_gif(&gif_frames, frame_duration(options.fps), path).map_err(|error| with_context(error, path))?;
+    }
+    Ok(())
 }
 
-///This function is drawing a line on a 2D grid represented by the frame array.
-///The line is drawn by setting the value of certain cells in the array to the ASCII values for a vertical bar (|) or a horizontal dash (-).
-fn draw_line(frame: &mut [[u8; SCREEN_WIDTH]; SCREEN_HEIGHT], start: [f32; 2], end: [f32; 2]) {
-    let [x0, y0] = start;
-    let [x1, y1] = end;
-    let [dx, dy] = [x1 - x0, y1 - y0];
-    if dy.abs() > dx.abs() {
-        let ymin = y0.min(y1);
-        let ymax = y0.max(y1);
-        let iymin = ymin.ceil() as usize;
-        let iymax = ymax.ceil() as usize;
-        let dxdy = dx / dy;
-        for iy in iymin..iymax {
-            let ix = ((iy as f32 - y0) * dxdy + x0) as usize;
-            frame[iy][ix] = b'|';
-        }
-    } else {
-        let xmin = x0.min(x1);
-        let xmax = x0.max(x1);
-        let ixmin = xmin.ceil() as usize;
-        let ixmax = xmax.ceil() as usize;
-        let dydx = dy / dx;
-        for ix in ixmin..ixmax {
-            let iy = ((ix as f32 - x0) * dydx + y0) as usize;
-            frame[iy][ix] = b'-';
+///This function runs the program, and if it fails prints the error's message to stderr and exits with a failure status.
+fn main() -> std::process::ExitCode {
+    match run() {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("simple-rust-cube: {}", error);
+            std::process::ExitCode::FAILURE
         }
     }
 }
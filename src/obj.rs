@@ -0,0 +1,124 @@
+//! Loading meshes from Wavefront OBJ files.
+//!
+//! Only the `v` (vertex) and `f` (face) lines are used. Normals, texture coordinates
+//! and everything else in the file are skipped.
+
+use crate::Vector;
+
+///The ObjError enum describes why an OBJ file could not be loaded.
+///Io is for when the file can't be read, and Parse is for a line that doesn't make sense, with its line number (starting at 1).
+///Empty is for a mesh with no vertices in it, which is most likely a mistake, like the wrong file or nothing piped in.
+#[derive(Debug)]
+pub enum ObjError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    Empty,
+}
+
+impl std::fmt::Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ObjError::Io(error) => write!(f, "could not read OBJ file: {}", error),
+            ObjError::Parse { line, message } => write!(f, "OBJ line {}: {}", line, message),
+            ObjError::Empty => write!(f, "OBJ input has no vertices"),
+        }
+    }
+}
+
+load_error!(ObjError);
+
+///This function loads the vertices and faces of the OBJ file at path. See parse_obj for the details.
+pub fn load_obj(path: &str) -> Result<(Vec<Vector>, Vec<Vec<u32>>), ObjError> {
+    let text = std::fs::read_to_string(path)?;
+    parse_obj(&text)
+}
+
+///This function reads an OBJ mesh from reader, which is how --stdin reads one from standard input. See parse_obj for the details.
+pub fn read_obj(mut reader: impl std::io::Read) -> Result<(Vec<Vector>, Vec<Vec<u32>>), ObjError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    parse_obj(&text)
+}
+
+///This function reads the vertices and faces out of the text of an OBJ file.
+///Each vertex becomes a Vector with a w of 1.0, and each face becomes a list of indices into the vertices, starting at 0.
+///OBJ indices start at 1, and negative indices count back from the last vertex read so far.
+///OBJ files list the corners of a face counterclockwise, but FACES lists them clockwise, so the order is reversed
+///to make cull treat the faces the same way. Text with no vertices in it is an ObjError::Empty.
+pub fn parse_obj(text: &str) -> Result<(Vec<Vector>, Vec<Vec<u32>>), ObjError> {
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: String| ObjError::Parse { line: number + 1, message };
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let mut xyz = [0.0; 3];
+                for value in xyz.iter_mut() {
+                    let word = words.next().ok_or_else(|| error("vertex needs x, y and z".to_string()))?;
+                    *value = word.parse().map_err(|_| error(format!("invalid coordinate '{}'", word)))?;
+                }
+                vertices.push(Vector::point(xyz[0], xyz[1], xyz[2]));
+            }
+            Some("f") => {
+                let mut face = Vec::new();
+                for word in words {
+                    // Corners can look like 1, 1/2, 1//3 or 1/2/3, and only the first number is the vertex.
+                    let index = word.split('/').next().unwrap_or(word);
+                    let index: i64 = index.parse().map_err(|_| error(format!("invalid vertex index '{}'", word)))?;
+                    let resolved = if index < 0 { vertices.len() as i64 + index } else { index - 1 };
+                    if resolved < 0 || resolved >= vertices.len() as i64 {
+                        return Err(error(format!("vertex index {} is out of range", index)));
+                    }
+                    face.push(resolved as u32);
+                }
+                if face.len() < 3 {
+                    return Err(error("face needs at least 3 corners".to_string()));
+                }
+                face.reverse();
+                faces.push(face);
+            }
+            _ => {}
+        }
+    }
+    if vertices.is_empty() {
+        return Err(ObjError::Empty);
+    }
+    Ok((vertices, faces))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_obj_reads_a_tetrahedron() {
+        let text = "# a tetrahedron\nv 1 1 1\nv -1 -1 1\nv -1 1 -1\nv 1 -1 -1\nvn 0 0 1\nf 1 2 3\nf 1/1 4/2 2/3\nf 1//1 3//1 4//1\nf -3 -1 -2\n";
+        let (vertices, faces) = parse_obj(text).unwrap();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(vertices[1], Vector::point(-1.0, -1.0, 1.0));
+        // The corners are reversed, so they go clockwise like FACES.
+        assert_eq!(faces, vec![vec![2, 1, 0], vec![1, 3, 0], vec![3, 2, 0], vec![2, 3, 1]]);
+    }
+
+    #[test]
+    fn read_obj_reads_a_mesh_through_a_reader() {
+        let input: &[u8] = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let (vertices, faces) = read_obj(input).unwrap();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(faces, vec![vec![3, 2, 1, 0]]);
+        assert!(matches!(read_obj(&b""[..]), Err(ObjError::Empty)));
+        assert!(matches!(read_obj(&b"v 0 0\n"[..]), Err(ObjError::Parse { line: 1, .. })));
+    }
+
+    #[test]
+    fn files_and_readers_with_no_vertices_are_both_empty() {
+        let path = std::env::temp_dir().join(format!("simple-rust-cube-empty-{}.obj", std::process::id()));
+        std::fs::write(&path, "# nothing here\n").unwrap();
+        let loaded = load_obj(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(ObjError::Empty)));
+        assert!(matches!(read_obj(&b"# nothing here\n"[..]), Err(ObjError::Empty)));
+        assert!(matches!(parse_obj(""), Err(ObjError::Empty)));
+    }
+}
@@ -0,0 +1,93 @@
+//! Rotations stored as quaternions.
+//!
+//! A unit quaternion holds a rotation as an axis and an angle, so rotations can be combined one after another
+//! without the gimbal lock of separate x, y and z angles. Rounding errors slowly build up when many are multiplied
+//! together, so the result should be normalized again every so often.
+
+use std::ops::Mul;
+
+use crate::{Matrix, Vector, EPSILON};
+
+///The Quaternion struct holds a rotation. w is the real part, and x, y and z are the axis scaled by the sine of half the angle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    ///This function returns the quaternion that doesn't rotate at all.
+    pub fn identity() -> Quaternion {
+        Quaternion { w: 1.0, x: 0.0, y: 0.0, z: 0.0 }
+    }
+
+    ///This function returns the rotation by angle radians around axis, following the right-hand rule like rotate_x, rotate_y and rotate_z.
+    ///The axis doesn't need a length of 1.0, and its w component is ignored.
+    pub fn from_axis_angle(axis: &Vector, angle: f32) -> Quaternion {
+        let [x, y, z, _] = axis.normalize().0;
+        let (s, c) = (angle * 0.5).sin_cos();
+        Quaternion { w: c, x: x * s, y: y * s, z: z * s }
+    }
+
+    ///This function returns the quaternion scaled to a length of 1.0, which is needed for it to be a rotation.
+    ///If it is too short to scale, the identity is returned instead.
+    pub fn normalize(&self) -> Quaternion {
+        let length = (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        if length < EPSILON {
+            return Quaternion::identity();
+        }
+        Quaternion { w: self.w / length, x: self.x / length, y: self.y / length, z: self.z / length }
+    }
+
+    ///This function returns the rotation matrix of the quaternion, with its columns laid out like the other matrices.
+    pub fn to_matrix(self) -> Matrix {
+        let Quaternion { w, x, y, z } = self;
+        Matrix([
+            [1.0 - 2.0 * (y * y + z * z),       2.0 * (x * y + w * z),       2.0 * (x * z - w * y), 0.0],
+            [      2.0 * (x * y - w * z), 1.0 - 2.0 * (x * x + z * z),       2.0 * (y * z + w * x), 0.0],
+            [      2.0 * (x * z + w * y),       2.0 * (y * z - w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+///Multiplying two quaternions combines their rotations. Like matrix_times_matrix, a * b rotates by b first and then by a.
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: Quaternion) -> Quaternion {
+        let Quaternion { w: aw, x: ax, y: ay, z: az } = self;
+        let Quaternion { w: bw, x: bx, y: by, z: bz } = other;
+        Quaternion {
+            w: aw * bw - ax * bx - ay * by - az * bz,
+            x: aw * bx + ax * bw + ay * bz - az * by,
+            y: aw * by - ax * bz + ay * bw + az * bx,
+            z: aw * bz + ax * by - ay * bx + az * bw,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{approx_eq_matrix, EPS};
+    use crate::{rotate_x, rotate_y, rotate_z, X_AXIS, Y_AXIS, Z_AXIS};
+
+    #[test]
+    fn to_matrix_matches_rotations_around_each_axis() {
+        for angle in [0.0, 0.4, -1.3, std::f32::consts::PI] {
+            assert!(approx_eq_matrix(&Quaternion::from_axis_angle(&X_AXIS, angle).to_matrix(), &rotate_x(angle), EPS));
+            assert!(approx_eq_matrix(&Quaternion::from_axis_angle(&Y_AXIS, angle).to_matrix(), &rotate_y(angle), EPS));
+            assert!(approx_eq_matrix(&Quaternion::from_axis_angle(&Z_AXIS, angle).to_matrix(), &rotate_z(angle), EPS));
+        }
+    }
+
+    #[test]
+    fn product_matches_product_of_matrices() {
+        let a = Quaternion::from_axis_angle(&X_AXIS, 0.5);
+        let b = Quaternion::from_axis_angle(&Y_AXIS, 1.2);
+        assert!(approx_eq_matrix(&(a * b).to_matrix(), &(rotate_x(0.5) * rotate_y(1.2)), EPS));
+    }
+}
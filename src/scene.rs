@@ -0,0 +1,200 @@
+//! Loading scenes from JSON files, so a scene with several objects can be set up without changing the code.
+//!
+//! A scene file looks like this, where everything but the shape of each object can be left out:
+//!
+//!   {
+//!     "camera": { "position": [0.0, 1.0, 4.0], "target": [0.0, 0.0, 0.0], "fov_y": 1.2 },
+//!     "objects": [
+//!       { "shape": "cube", "position": [-1.5, 0.0, 0.0], "rotation": [0.0, 0.5, 0.0], "scale": [0.5, 0.5, 0.5] },
+//!       { "shape": "sphere", "position": [1.5, 0.0, 0.0] }
+//!     ]
+//!   }
+//!
+//! The shapes are the ones in shapes::NAMES. Rotations are in radians around the x, y and z axes, see object_model.
+
+use serde::Deserialize;
+
+use crate::{cross, rotate_x, rotate_y, rotate_z, scale, shapes, translation, Camera, Matrix, Object, Vector};
+
+///The SceneError enum describes why a scene file could not be loaded.
+///Io is for when the file can't be read, Json is for text that isn't a scene, and UnknownShape is for a shape that isn't in shapes::NAMES.
+///InvalidCamera is for a camera that can't be pointed anywhere, and says what is wrong with it, see check_camera.
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnknownShape(String),
+    InvalidCamera(&'static str),
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SceneError::Io(error) => write!(f, "could not read scene file: {}", error),
+            SceneError::Json(error) => write!(f, "invalid scene: {}", error),
+            SceneError::UnknownShape(name) => write!(f, "invalid scene: unknown shape '{}', expected one of {}", name, shapes::NAMES.join(", ")),
+            SceneError::InvalidCamera(problem) => write!(f, "invalid scene: the camera's {}", problem),
+        }
+    }
+}
+
+load_error!(SceneError);
+
+impl From<serde_json::Error> for SceneError {
+    fn from(error: serde_json::Error) -> SceneError {
+        SceneError::Json(error)
+    }
+}
+
+///MIN_LENGTH is the shortest a direction of the camera can be and still point somewhere.
+const MIN_LENGTH : f32 = 1e-6;
+
+///The SceneFile struct is what a scene file holds. The camera is the default one if it's left out.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default)]
+    camera: CameraFile,
+    objects: Vec<ObjectFile>,
+}
+
+///The CameraFile struct is the camera of a scene file. Anything that is left out is the same as in Camera::default().
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraFile {
+    position: Option<[f32; 3]>,
+    target: Option<[f32; 3]>,
+    up: Option<[f32; 3]>,
+    fov_y: Option<f32>,
+}
+
+///The ObjectFile struct is one object in a scene file. By default it is at the origin, not turned, and its normal size.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectFile {
+    shape: String,
+    #[serde(default)]
+    position: [f32; 3],
+    #[serde(default)]
+    rotation: [f32; 3],
+    #[serde(default = "unit_scale")]
+    scale: [f32; 3],
+}
+
+///This function returns the scale of an object that doesn't give one.
+fn unit_scale() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+///This function loads the camera and objects of the scene file at path. See parse_scene for the details.
+pub fn load_scene(path: &str) -> Result<(Camera, Vec<Object>), SceneError> {
+    let text = std::fs::read_to_string(path)?;
+    parse_scene(&text)
+}
+
+///This function reads the camera and objects out of the text of a scene file, and builds the model matrix of each object
+///with object_model.
+pub fn parse_scene(text: &str) -> Result<(Camera, Vec<Object>), SceneError> {
+    let file: SceneFile = serde_json::from_str(text)?;
+    let mut camera = Camera::default();
+    if let Some([x, y, z]) = file.camera.position {
+        camera.position = Vector::point(x, y, z);
+    }
+    if let Some([x, y, z]) = file.camera.target {
+        camera.target = Vector::point(x, y, z);
+    }
+    if let Some([x, y, z]) = file.camera.up {
+        camera.up = Vector::direction(x, y, z);
+    }
+    camera.fov_y = file.camera.fov_y.unwrap_or(camera.fov_y);
+    check_camera(&camera)?;
+
+    let mut objects = Vec::with_capacity(file.objects.len());
+    for object in &file.objects {
+        let model = object_model(object.position, object.rotation, object.scale);
+        objects.push(shape_object(&object.shape, model)?);
+    }
+    Ok((camera, objects))
+}
+
+///This function checks that the camera can be pointed at its target, which look_at needs to build the view matrix.
+///The position and target have to be different points, and up has to have a length and not point along the line between them,
+///or there is no single direction that is to the right of the camera.
+fn check_camera(camera: &Camera) -> Result<(), SceneError> {
+    let forward = camera.target - camera.position;
+    if forward.length() < MIN_LENGTH {
+        return Err(SceneError::InvalidCamera("position and target are the same point"));
+    }
+    if camera.up.length() < MIN_LENGTH {
+        return Err(SceneError::InvalidCamera("up has no length"));
+    }
+    if cross(&forward.normalize(), &camera.up.normalize()).length() < MIN_LENGTH {
+        return Err(SceneError::InvalidCamera("up points along the line from the position to the target"));
+    }
+    Ok(())
+}
+
+///This function builds the model matrix of an object, which scales it, then turns it around the x axis, the y axis and the z axis
+///in that order, and then moves it to position.
+fn object_model(position: [f32; 3], rotation: [f32; 3], size: [f32; 3]) -> Matrix {
+    let [x, y, z] = position;
+    let [rx, ry, rz] = rotation;
+    let [sx, sy, sz] = size;
+    translation(x, y, z) * rotate_z(rz) * rotate_y(ry) * rotate_x(rx) * scale(sx, sy, sz)
+}
+
+///This function makes an object of the shape with the given name, placed by model.
+fn shape_object(name: &str, model: Matrix) -> Result<Object, SceneError> {
+    if name == "sphere" {
+        let (vertices, lines) = shapes::uv_sphere(shapes::SPHERE_RINGS, shapes::SPHERE_SEGMENTS);
+        return Ok(Object::new(vertices, Vec::new(), lines, model));
+    }
+    let (vertices, faces) = shapes::by_name(name).ok_or_else(|| SceneError::UnknownShape(name.to_string()))?;
+    let faces = faces.iter().map(|face| face.iter().map(|&v| v as u32).collect()).collect();
+    Ok(Object::new(vertices, faces, Vec::new(), model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{approx_eq_matrix, approx_eq_vector, EPS};
+
+    #[test]
+    fn parse_scene_reads_two_objects_and_the_camera() {
+        let text = r#"{
+            "camera": { "position": [0.0, 1.0, 4.0] },
+            "objects": [
+                { "shape": "cube", "position": [-1.5, 0.0, 0.0], "scale": [0.5, 0.5, 0.5] },
+                { "shape": "sphere", "position": [1.5, 0.0, 0.0], "rotation": [0.0, 0.5, 0.0] }
+            ]
+        }"#;
+        let (camera, objects) = parse_scene(text).unwrap();
+        assert!(approx_eq_vector(&camera.position, &Vector::point(0.0, 1.0, 4.0), EPS));
+        assert_eq!(camera.fov_y, Camera::default().fov_y);
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].faces.len(), 6);
+        assert!(approx_eq_matrix(&objects[0].model, &(translation(-1.5, 0.0, 0.0) * scale(0.5, 0.5, 0.5)), EPS));
+        assert!(objects[1].faces.is_empty() && !objects[1].lines.is_empty());
+        assert!(approx_eq_matrix(&objects[1].model, &(translation(1.5, 0.0, 0.0) * rotate_y(0.5)), EPS));
+    }
+
+    #[test]
+    fn invalid_scenes_are_errors() {
+        assert!(matches!(parse_scene(r#"{ "objects": [{ "shape": "teapot" }] }"#), Err(SceneError::UnknownShape(name)) if name == "teapot"));
+        assert!(matches!(parse_scene(r#"{ "objects": [{ "shape": "cube", "colour": 1 }] }"#), Err(SceneError::Json(_))));
+        let error: std::io::Error = parse_scene("").unwrap_err().into();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn cameras_that_cannot_be_pointed_are_errors() {
+        let scene = |camera: &str| parse_scene(&format!(r#"{{ "camera": {}, "objects": [{{ "shape": "cube" }}] }}"#, camera));
+        let same_point = scene(r#"{ "position": [1.0, 2.0, 3.0], "target": [1.0, 2.0, 3.0] }"#);
+        assert!(matches!(same_point, Err(SceneError::InvalidCamera(problem)) if problem.contains("same point")));
+        assert!(matches!(scene(r#"{ "up": [0.0, 0.0, 0.0] }"#), Err(SceneError::InvalidCamera(_))));
+        assert!(matches!(scene(r#"{ "up": [0.0, 0.0, 1e-9] }"#), Err(SceneError::InvalidCamera(_))));
+        let parallel = scene(r#"{ "position": [0.0, 4.0, 0.0], "up": [0.0, 2.0, 0.0] }"#).unwrap_err();
+        assert!(parallel.to_string().starts_with("invalid scene: the camera's up"));
+        assert!(scene(r#"{ "position": [0.0, 4.0, 4.0] }"#).is_ok());
+    }
+}
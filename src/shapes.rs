@@ -0,0 +1,214 @@
+//! Built-in solids that can be drawn instead of loading a mesh.
+//!
+//! Every shape uses the same form as VERTICES and FACES: points with a w of 1.0, and faces that
+//! list their corners clockwise as seen from outside the shape, so that cull hides the back faces.
+//! The sphere is a wireframe instead, made of lines between pairs of points, see uv_sphere.
+
+use crate::{Vector, FACES, VERTICES};
+
+///NAMES holds the names that can be passed to --shape.
+pub const NAMES : [&str; 5] = ["cube", "tetrahedron", "octahedron", "pyramid", "sphere"];
+
+///SPHERE_RINGS and SPHERE_SEGMENTS are how many bands of latitude and longitude the --shape sphere wireframe has.
+pub const SPHERE_RINGS : usize = 8;
+pub const SPHERE_SEGMENTS : usize = 16;
+
+///FLOOR_CELLS and FLOOR_SIZE are how many squares there are along each side of the --grid floor, and how wide it is.
+pub const FLOOR_CELLS : usize = 8;
+pub const FLOOR_SIZE : f32 = 8.0;
+
+///FLOOR_Y is the height of the --grid floor, which is level with the bottom of the cube.
+pub const FLOOR_Y : f32 = -1.0;
+
+///This function returns the shape with the given name, or None if there is no shape with that name.
+///The sphere has no faces, so it is not returned here.
+pub fn by_name(name: &str) -> Option<(Vec<Vector>, Vec<Vec<u8>>)> {
+    match name {
+        "cube" => Some(cube()),
+        "tetrahedron" => Some(tetrahedron()),
+        "octahedron" => Some(octahedron()),
+        "pyramid" => Some(square_pyramid()),
+        _ => None,
+    }
+}
+
+///This function returns the cube from VERTICES and FACES.
+pub fn cube() -> (Vec<Vector>, Vec<Vec<u8>>) {
+    (VERTICES.to_vec(), FACES.iter().map(|face| face.to_vec()).collect())
+}
+
+///This function returns a tetrahedron made from four corners of the cube, so it fits inside it.
+pub fn tetrahedron() -> (Vec<Vector>, Vec<Vec<u8>>) {
+    let vertices = vec![
+        Vector([ 1.0,  1.0,  1.0, 1.0]),
+        Vector([-1.0, -1.0,  1.0, 1.0]),
+        Vector([-1.0,  1.0, -1.0, 1.0]),
+        Vector([ 1.0, -1.0, -1.0, 1.0]),
+    ];
+    let faces = vec![
+        vec![0, 1, 2],
+        vec![0, 3, 1],
+        vec![0, 2, 3],
+        vec![1, 3, 2],
+    ];
+    (vertices, faces)
+}
+
+///This function returns an octahedron with a corner 1.0 along each direction of each axis.
+pub fn octahedron() -> (Vec<Vector>, Vec<Vec<u8>>) {
+    let vertices = vec![
+        Vector([ 1.0,  0.0,  0.0, 1.0]),
+        Vector([-1.0,  0.0,  0.0, 1.0]),
+        Vector([ 0.0,  1.0,  0.0, 1.0]),
+        Vector([ 0.0, -1.0,  0.0, 1.0]),
+        Vector([ 0.0,  0.0,  1.0, 1.0]),
+        Vector([ 0.0,  0.0, -1.0, 1.0]),
+    ];
+    let faces = vec![
+        vec![4, 2, 0],
+        vec![2, 4, 1],
+        vec![3, 4, 0],
+        vec![4, 3, 1],
+        vec![2, 5, 0],
+        vec![5, 2, 1],
+        vec![5, 3, 0],
+        vec![3, 5, 1],
+    ];
+    (vertices, faces)
+}
+
+///This function returns a pyramid with a square base the same size as the bottom of the cube, and its tip at the top of the cube.
+pub fn square_pyramid() -> (Vec<Vector>, Vec<Vec<u8>>) {
+    let vertices = vec![
+        Vector([-1.0, -1.0, -1.0, 1.0]),
+        Vector([ 1.0, -1.0, -1.0, 1.0]),
+        Vector([ 1.0, -1.0,  1.0, 1.0]),
+        Vector([-1.0, -1.0,  1.0, 1.0]),
+        Vector([ 0.0,  1.0,  0.0, 1.0]),
+    ];
+    let faces = vec![
+        vec![3, 2, 1, 0],
+        vec![1, 4, 0],
+        vec![2, 4, 1],
+        vec![3, 4, 2],
+        vec![0, 4, 3],
+    ];
+    (vertices, faces)
+}
+
+///AXIS_LENGTH is how long the lines drawn by --axes are, which is long enough to stick out of the cube.
+pub const AXIS_LENGTH : f32 = 1.5;
+
+///This function returns the x, y and z axes as three lines from the origin, each length long, in that order.
+pub fn axes(length: f32) -> (Vec<Vector>, Vec<[u32; 2]>) {
+    let vertices = vec![
+        Vector::point(0.0, 0.0, 0.0),
+        Vector::point(length, 0.0, 0.0),
+        Vector::point(0.0, length, 0.0),
+        Vector::point(0.0, 0.0, length),
+    ];
+    (vertices, vec![[0, 1], [0, 2], [0, 3]])
+}
+
+///This function returns a square grid of lines lying flat at a height of FLOOR_Y, centered under the origin,
+///which is size across and divided into cells by cells squares along each side.
+///Each line goes all the way across, so there are cells + 1 lines along the x axis and the same number along the z axis.
+pub fn floor_grid(cells: usize, size: f32) -> (Vec<Vector>, Vec<[u32; 2]>) {
+    let half = size * 0.5;
+    let mut vertices = Vec::with_capacity(4 * (cells + 1));
+    let mut lines = Vec::with_capacity(2 * (cells + 1));
+    for i in 0..=cells {
+        let offset = -half + size * i as f32 / cells.max(1) as f32;
+        let first = vertices.len() as u32;
+        vertices.push(Vector::point(-half, FLOOR_Y, offset));
+        vertices.push(Vector::point(half, FLOOR_Y, offset));
+        vertices.push(Vector::point(offset, FLOOR_Y, -half));
+        vertices.push(Vector::point(offset, FLOOR_Y, half));
+        lines.push([first, first + 1]);
+        lines.push([first + 2, first + 3]);
+    }
+    (vertices, lines)
+}
+
+///This function returns a wireframe sphere with a radius of 1.0, made of lines of latitude and longitude.
+///There are rings + 1 circles of latitude from the top pole to the bottom pole, and each of them has segments + 1 points,
+///where the last point is in the same place as the first so the circle closes. The poles are stored as whole circles too.
+///The lines join each point to the next one around its circle and to the one below it on the next circle.
+///rings and segments are at least 1, so 0 gives the same as 1 instead of dividing by zero.
+pub fn uv_sphere(rings: usize, segments: usize) -> (Vec<Vector>, Vec<[u32; 2]>) {
+    let (rings, segments) = (rings.max(1), segments.max(1));
+    let mut vertices = Vec::with_capacity((rings + 1) * (segments + 1));
+    for ring in 0..=rings {
+        let theta = std::f32::consts::PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            let phi = std::f32::consts::TAU * segment as f32 / segments as f32;
+            vertices.push(Vector::point(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()));
+        }
+    }
+    let index = |ring: usize, segment: usize| (ring * (segments + 1) + segment) as u32;
+    let mut lines = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            // The circles at the poles have no length, so they get no lines around them.
+            if ring > 0 {
+                lines.push([index(ring, segment), index(ring, segment + 1)]);
+            }
+            lines.push([index(ring, segment), index(ring + 1, segment)]);
+        }
+    }
+    (vertices, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uv_sphere_has_the_right_counts_and_radius() {
+        let (vertices, lines) = uv_sphere(SPHERE_RINGS, SPHERE_SEGMENTS);
+        assert_eq!(vertices.len(), (SPHERE_RINGS + 1) * (SPHERE_SEGMENTS + 1));
+        assert_eq!(lines.len(), (2 * SPHERE_RINGS - 1) * SPHERE_SEGMENTS);
+        assert!(vertices.iter().all(|v| (v.length() - 1.0).abs() < 1e-5 && v.0[3] == 1.0));
+        assert!(lines.iter().flatten().all(|&i| (i as usize) < vertices.len()));
+    }
+
+    #[test]
+    fn uv_sphere_with_no_rings_or_segments_has_no_nan() {
+        for (rings, segments) in [(0, 0), (0, 4), (4, 0)] {
+            let (vertices, lines) = uv_sphere(rings, segments);
+            assert!(vertices.iter().all(|v| v.0.iter().all(|x| x.is_finite())));
+            assert!(!lines.is_empty());
+            assert!(vertices.iter().all(|v| (v.length() - 1.0).abs() < 1e-5));
+        }
+    }
+
+    #[test]
+    fn each_shape_has_the_right_number_of_vertices_and_faces() {
+        for (name, vertex_count, face_count) in [("cube", 8, 6), ("tetrahedron", 4, 4), ("octahedron", 6, 8), ("pyramid", 5, 5)] {
+            let (vertices, faces) = by_name(name).unwrap();
+            assert_eq!((vertices.len(), faces.len()), (vertex_count, face_count), "{}", name);
+            assert!(faces.iter().flatten().all(|&i| (i as usize) < vertices.len()), "{}", name);
+        }
+        assert!(by_name("teapot").is_none());
+    }
+
+    #[test]
+    fn floor_grid_has_two_lines_for_each_cell_plus_one() {
+        for cells in [1, 4, FLOOR_CELLS] {
+            let (vertices, lines) = floor_grid(cells, FLOOR_SIZE);
+            assert_eq!(lines.len(), 2 * (cells + 1));
+            assert!(lines.iter().flatten().all(|&i| (i as usize) < vertices.len()));
+            assert!(vertices.iter().all(|v| v.0[1] == FLOOR_Y && v.0[0].abs() <= FLOOR_SIZE * 0.5 && v.0[2].abs() <= FLOOR_SIZE * 0.5));
+        }
+    }
+
+    #[test]
+    fn axes_are_three_lines_along_the_basis_directions() {
+        let (vertices, lines) = axes(AXIS_LENGTH);
+        assert_eq!(lines.len(), 3);
+        for ([start, end], axis) in lines.into_iter().zip([crate::X_AXIS, crate::Y_AXIS, crate::Z_AXIS]) {
+            assert_eq!(vertices[start as usize], Vector::point(0.0, 0.0, 0.0));
+            assert_eq!(vertices[end as usize] - vertices[start as usize], axis * AXIS_LENGTH);
+        }
+    }
+}
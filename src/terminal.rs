@@ -0,0 +1,298 @@
+//! Setting up the terminal for the animation and putting it back afterwards.
+//!
+//! The cursor is hidden while the cube is drawn. It is shown again, and any color is reset,
+//! when TerminalGuard is dropped, which happens when main returns, after Ctrl-C, and after a panic.
+//!
+//! With --raw the frames are written as records instead, so that they can be saved and played back later.
+//! The output starts with a header line, RAW_HEADER followed by the width and height of the screen:
+//!
+//!   simple-rust-cube-raw 1 80 40
+//!
+//! and then each frame is a line with the time since the header was written in milliseconds and the length of the frame in bytes,
+//! followed by exactly that many bytes, which are what present would have written to the terminal:
+//!
+//!   0 3304
+//!   <3304 bytes>
+//!   30 3296
+//!   <3296 bytes>
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Framebuffer;
+
+///These are the escape sequences that hide and show the cursor, and reset the colors and other formatting.
+pub const HIDE_CURSOR : &str = "\x1b[?25l";
+pub const SHOW_CURSOR : &str = "\x1b[?25h";
+pub const RESET_FORMAT : &str = "\x1b[0m";
+
+///These are the escape sequences that move the cursor to the top left corner, and clear the whole screen.
+pub const CURSOR_HOME : &str = "\x1b[H";
+pub const CLEAR_SCREEN : &str = "\x1b[2J";
+
+///RAW_HEADER is the start of the first line written by --raw, with the version of the record format, see raw_header.
+pub const RAW_HEADER : &str = "simple-rust-cube-raw 1";
+
+///INTERRUPTED is set by the Ctrl-C handler, so the render loop can stop and let everything be cleaned up.
+static INTERRUPTED : AtomicBool = AtomicBool::new(false);
+
+///RESIZED is set by the SIGWINCH handler when the terminal changes size, so the render loop can pick up the new size.
+static RESIZED : AtomicBool = AtomicBool::new(false);
+
+///This function returns the text that puts the terminal back to normal.
+pub fn restore_sequence() -> String {
+    format!("{}{}", RESET_FORMAT, SHOW_CURSOR)
+}
+
+///This function returns everything that is written to the terminal for one frame: the escape sequence that moves
+///the cursor to the top left corner, followed by every row of the frame, each ending in a newline.
+///Moving the cursor first means each frame lands in the same place even if a line has wrapped.
+pub fn compose_frame(frame: &Framebuffer) -> String {
+    format!("{}{}", CURSOR_HOME, frame.render_to_string())
+}
+
+///This function is like compose_frame, but adds the escape sequences for the color of each cell.
+pub fn compose_colored_frame(frame: &Framebuffer) -> String {
+    format!("{}{}", CURSOR_HOME, frame.render_to_colored_string())
+}
+
+///This function writes a frame to out, drawing over the previous one. On the first frame the screen is cleared as well,
+///to remove whatever was there before.
+///The whole frame is put together first and written with a single call, so the terminal never shows half of one frame
+///and half of the one before.
+pub fn present(out: &mut impl Write, frame: &Framebuffer, color: bool, first_frame: bool) -> std::io::Result<()> {
+    out.write_all(frame_text(frame, color, first_frame).as_bytes())?;
+    out.flush()
+}
+
+///This function returns the text that present writes for a frame.
+fn frame_text(frame: &Framebuffer, color: bool, first_frame: bool) -> String {
+    let mut text = String::new();
+    if first_frame {
+        text.push_str(CLEAR_SCREEN);
+    }
+    if color {
+        text.push_str(&compose_colored_frame(frame));
+    } else {
+        text.push_str(&compose_frame(frame));
+    }
+    text
+}
+
+///This function returns the header line that --raw starts with, for a screen width cells wide and height cells high.
+pub fn raw_header(width: usize, height: usize) -> String {
+    format!("{} {} {}\n", RAW_HEADER, width, height)
+}
+
+///This function writes a frame to out as a --raw record, elapsed after the header, in the format described at the top of this module.
+///The frame is the same text that present writes, so playing the records back draws the same thing.
+pub fn write_raw_record(out: &mut impl Write, elapsed: std::time::Duration, frame: &Framebuffer, color: bool, first_frame: bool) -> std::io::Result<()> {
+    let text = frame_text(frame, color, first_frame);
+    let mut record = format!("{} {}\n", elapsed.as_millis(), text.len());
+    record.push_str(&text);
+    out.write_all(record.as_bytes())?;
+    out.flush()
+}
+
+///This function turns the bytes of a frame's text, like the text that present writes, back into a str.
+///Bytes that aren't valid UTF-8 give an InvalidData error instead of a panic, so they can be returned from main like any other error.
+pub fn frame_str(bytes: &[u8]) -> std::io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+}
+
+///This function returns the number of columns and rows that fit in the terminal that standard output goes to,
+///with one row left over for the cursor to sit in, so the frame doesn't scroll the screen.
+///None is returned when standard output isn't a terminal, or the terminal doesn't know its size,
+///and always on systems other than Unix, where the default size is used instead.
+#[cfg(unix)]
+pub fn detect_terminal_size() -> Option<(usize, usize)> {
+    // SAFETY: winsize is a plain C struct that TIOCGWINSZ fills in completely.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+        return None;
+    }
+    screen_size(size.ws_col, size.ws_row)
+}
+
+#[cfg(not(unix))]
+pub fn detect_terminal_size() -> Option<(usize, usize)> {
+    None
+}
+
+///This function returns the size of screen to draw for a terminal with the given number of columns and rows, see detect_terminal_size.
+///Some terminals report a size of 0 when they don't know it, and a terminal with fewer than two rows has no room for a frame,
+///so both give None.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn screen_size(columns: u16, rows: u16) -> Option<(usize, usize)> {
+    if columns == 0 || rows < 2 {
+        return None;
+    }
+    Some((columns as usize, rows as usize - 1))
+}
+
+///This function returns the size to change the screen to after the terminal has been resized, given the current size
+///and the size that detect_terminal_size found. None is returned if the size couldn't be found or hasn't changed,
+///so the screen stays as it is.
+pub fn new_size(current: (usize, usize), detected: Option<(usize, usize)>) -> Option<(usize, usize)> {
+    detected.filter(|&size| size != current)
+}
+
+///The TerminalGuard struct hides the cursor while it exists, and restores the terminal when it is dropped.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    ///This function hides the cursor and sets up the Ctrl-C, resize and panic handlers.
+    pub fn new() -> TerminalGuard {
+        install_interrupt_handler();
+        install_resize_handler();
+        // The panic message is printed after the terminal is restored, so it can be read normally.
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            print!("{}", restore_sequence());
+            let _ = std::io::stdout().flush();
+            default_hook(info);
+        }));
+        print!("{}", HIDE_CURSOR);
+        let _ = std::io::stdout().flush();
+        TerminalGuard
+    }
+}
+
+impl Default for TerminalGuard {
+    fn default() -> TerminalGuard {
+        TerminalGuard::new()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        print!("{}", restore_sequence());
+        let _ = std::io::stdout().flush();
+    }
+}
+
+///This function returns true once Ctrl-C has been pressed.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+///This function returns true if the terminal has been resized since it was last called.
+pub fn take_resized() -> bool {
+    RESIZED.swap(false, Ordering::Relaxed)
+}
+
+///This is the Ctrl-C (SIGINT) handler. It only sets INTERRUPTED, because very little is safe to do inside a signal handler.
+///The signal handlers are only installed on Unix. Elsewhere Ctrl-C stops the program straight away, and resizes aren't noticed.
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+///This function makes Ctrl-C set INTERRUPTED instead of stopping the program straight away.
+#[cfg(unix)]
+fn install_interrupt_handler() {
+    let handler: extern "C" fn(libc::c_int) = on_interrupt;
+    // SAFETY: on_interrupt only stores to an atomic, which is safe to do in a signal handler.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+///This is the SIGWINCH handler, which is called when the terminal is resized. Like on_interrupt, it only sets a flag.
+#[cfg(unix)]
+extern "C" fn on_resize(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::Relaxed);
+}
+
+///This function makes resizing the terminal set RESIZED.
+#[cfg(unix)]
+fn install_resize_handler() {
+    let handler: extern "C" fn(libc::c_int) = on_resize;
+    // SAFETY: on_resize only stores to an atomic, which is safe to do in a signal handler.
+    unsafe {
+        libc::signal(libc::SIGWINCH, handler as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_interrupt_handler() {}
+
+#[cfg(not(unix))]
+fn install_resize_handler() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_sequence_shows_the_cursor_and_resets_the_colors() {
+        assert_eq!(restore_sequence(), "\x1b[0m\x1b[?25h");
+        assert!(restore_sequence().contains(SHOW_CURSOR) && !restore_sequence().contains(HIDE_CURSOR));
+    }
+
+    #[test]
+    fn present_moves_the_cursor_home_without_a_stray_semicolon() {
+        let mut frame = Framebuffer::new(3, 2);
+        frame.set(1, 0, '#');
+        let mut out = Vec::new();
+        present(&mut out, &frame, false, true).unwrap();
+        present(&mut out, &frame, false, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, format!("{}{} # \n   \n{} # \n   \n", CLEAR_SCREEN, CURSOR_HOME, CURSOR_HOME));
+        assert!(!text.contains(';'));
+    }
+
+    #[test]
+    fn compose_frame_has_one_row_for_each_line_of_the_frame() {
+        let frame = Framebuffer::new(5, 4);
+        let text = compose_frame(&frame);
+        let rows = text.strip_prefix(CURSOR_HOME).unwrap();
+        assert!(rows.ends_with('\n'));
+        assert_eq!(rows.split_terminator('\n').collect::<Vec<_>>(), vec!["     "; 4]);
+    }
+
+    #[test]
+    fn unknown_terminal_sizes_fall_back_to_the_default() {
+        assert_eq!(screen_size(0, 50), None);
+        assert_eq!(screen_size(100, 1), None);
+        assert_eq!(screen_size(100, 0), None);
+        assert_eq!(screen_size(100, 50), Some((100, 49)));
+        // Without a size the screen is the default size, as main does.
+        let options = match screen_size(0, 0) {
+            Some((width, height)) => crate::Options { width, height, ..crate::Options::default() },
+            None => crate::Options::default(),
+        };
+        assert_eq!((options.width, options.height), (80, 40));
+    }
+
+    #[test]
+    fn new_size_only_changes_the_screen_when_the_size_does() {
+        assert_eq!(new_size((80, 40), Some((120, 30))), Some((120, 30)));
+        assert_eq!(new_size((80, 40), Some((80, 40))), None);
+        assert_eq!(new_size((80, 40), None), None);
+        // The framebuffer made for the new size has only the new rows, so nothing is left over from the old one.
+        let size = new_size((80, 40), Some((20, 3))).unwrap();
+        let frame = Framebuffer::new(size.0, size.1);
+        assert_eq!(compose_frame(&frame), format!("{}{}", CURSOR_HOME, format!("{}\n", " ".repeat(20)).repeat(3)));
+    }
+
+    #[test]
+    fn raw_record_has_the_time_and_the_length_of_the_frame() {
+        let mut frame = Framebuffer::new(4, 2);
+        frame.set(1, 1, '#');
+        assert_eq!(raw_header(4, 2), format!("{} 4 2\n", RAW_HEADER));
+        let mut out = Vec::new();
+        write_raw_record(&mut out, std::time::Duration::from_millis(1234), &frame, false, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let (header, payload) = text.split_once('\n').unwrap();
+        assert_eq!(header, format!("1234 {}", payload.len()));
+        assert_eq!(payload, frame_text(&frame, false, false));
+    }
+
+    #[test]
+    fn frame_str_returns_an_error_for_bytes_that_are_not_utf8() {
+        assert_eq!(frame_str("# \u{2502}\n".as_bytes()).unwrap(), "# \u{2502}\n");
+        let error = frame_str(&[b'#', 0xff, b'\n']).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}